@@ -0,0 +1,172 @@
+use std::{
+	env,
+	error::Error,
+	fs,
+	path::Path,
+	process
+};
+
+use meshio::{
+	detect,
+	Format,
+	Language,
+	LocalizedStringMap,
+	LocalizedStringMapExt,
+	Registry,
+	SceneF,
+	Severity,
+	ValidationOptions,
+	validate_with
+};
+
+const USAGE: &str = "\
+usage: meshio <command> [options] <files>
+
+commands:
+    convert [--keep-axes] <input> <output>
+        convert a model, choosing both formats from the files; the scene is
+        re-expressed in the output format's axes and units unless --keep-axes
+    info <input>
+        print element counts and bounds
+    validate <input>
+        check that a model imports cleanly and is consistent
+    dump --json <input>
+        print the imported scene as JSON
+";
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+/// Returns every format the tool can read and write
+fn registry() -> Registry {
+	let mut registry = Registry::new();
+	registry.register(meshio_mmd::Pmx);
+	registry.register(meshio_wavefront::Obj);
+	registry.register_exporter(meshio_wavefront::Obj);
+	registry
+}
+
+fn import(registry: &Registry, path: &str) -> CliResult<(SceneF, Format)> {
+	let bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+	let format = detect(&bytes, Some(Path::new(path)))
+		.ok_or_else(|| format!("{}: {}", path, meshio::Error::UnknownFormat))?;
+	let importer = registry.importer(format)
+		.ok_or_else(|| format!("{}: {} files cannot be read", path, format.name()))?;
+	let scene = importer.import(&bytes).map_err(|e| format!("{}: {}", path, e))?;
+
+	Ok((scene, format))
+}
+
+fn display_name(name: &LocalizedStringMap) -> &str {
+	name.localized(&Language::English).unwrap_or("")
+}
+
+fn convert(registry: &Registry, input: &str, output: &str, keep_axes: bool) -> CliResult<()> {
+	let (mut scene, _) = import(registry, input)?;
+	let format = detect(&[], Some(Path::new(output)))
+		.ok_or_else(|| format!("{}: unknown output format", output))?;
+
+	if !keep_axes {
+		scene.convert_to(format.coordinate_system()).map_err(|e| format!("{}: {}", input, e))?;
+	}
+
+	let bytes = registry.export(&scene, format).map_err(|e| format!("{}: {}", output, e))?;
+	fs::write(output, bytes).map_err(|e| format!("{}: {}", output, e))?;
+	Ok(())
+}
+
+fn info(registry: &Registry, input: &str) -> CliResult<()> {
+	let (scene, format) = import(registry, input)?;
+	let cs = &scene.coordinate_system;
+
+	println!("format: {}", format.name());
+	println!("name: {}", display_name(&scene.name));
+	println!("axes: {:?} up, {:?} forward, {:?} handed, {} m per unit",
+		cs.up, cs.forward, cs.handedness, cs.meters_per_unit);
+	println!("materials: {}", scene.materials.len());
+	println!("scene nodes: {}, lights: {}, cameras: {}", scene.nodes.len(), scene.lights.len(), scene.cameras.len());
+	println!("objects: {}", scene.objects.len());
+
+	for (i, object) in scene.objects.iter().enumerate() {
+		let vertices: usize = object.nodes.iter().map(|n| n.vertices.len()).sum();
+		let faces: usize = object.nodes.iter().map(|n| n.faces.len()).sum();
+
+		println!("  [{}] {}", i, display_name(&object.name));
+		println!("    nodes: {}, vertices: {}, faces: {}", object.nodes.len(), vertices, faces);
+		println!("    uv sets: {}, color sets: {}",
+			object.nodes.iter().map(|n| n.uv_set_count()).max().unwrap_or(0),
+			object.nodes.iter().map(|n| n.color_set_count()).max().unwrap_or(0));
+		println!("    bones: {}, morphs: {}, animations: {}",
+			object.skeleton.as_ref().map_or(0, |s| s.len()), object.morphs.len(), object.animations.len());
+		println!("    rigid bodies: {}, constraints: {}",
+			object.physics.bodies.len(), object.physics.constraints.len());
+
+		if let Some(bounds) = object.bounds() {
+			println!("    bounds: min ({}, {}, {}), max ({}, {}, {}), radius {}",
+				bounds.min.x, bounds.min.y, bounds.min.z,
+				bounds.max.x, bounds.max.y, bounds.max.z,
+				bounds.radius_or_corner());
+		}
+	}
+
+	Ok(())
+}
+
+/// Prints problems with the scene and returns whether it is valid
+fn validate(registry: &Registry, input: &str) -> CliResult<bool> {
+	let (scene, _) = import(registry, input)?;
+	let options = ValidationOptions {
+		texture_root: Path::new(input).parent().map(Path::to_path_buf),
+		..Default::default()
+	};
+	let diagnostics = validate_with(&scene, &options);
+
+	for diagnostic in diagnostics.iter() {
+		println!("{}", diagnostic);
+	}
+
+	let valid = diagnostics.iter().all(|d| d.severity != Severity::Error);
+	if valid {
+		println!("{}: ok", input);
+	}
+	Ok(valid)
+}
+
+fn dump(registry: &Registry, input: &str) -> CliResult<()> {
+	let (scene, _) = import(registry, input)?;
+	println!("{}", serde_json::to_string_pretty(&scene)?);
+	Ok(())
+}
+
+fn run(args: &[String]) -> CliResult<i32> {
+	let registry = registry();
+	let flags: Vec<&str> = args.iter().skip(1).map(String::as_str).filter(|a| a.starts_with("--")).collect();
+	let files: Vec<&str> = args.iter().skip(1).map(String::as_str).filter(|a| !a.starts_with("--")).collect();
+	let command = args.first().map(String::as_str);
+
+	// Each command takes exactly the flags its usage lists, so a mistyped flag is not ignored
+	match (command, flags.as_slice(), files.as_slice()) {
+		(Some("convert"), [], [input, output]) => convert(&registry, input, output, false)?,
+		(Some("convert"), ["--keep-axes"], [input, output]) => convert(&registry, input, output, true)?,
+		(Some("info"), [], [input]) => info(&registry, input)?,
+		(Some("validate"), [], [input]) => return Ok(if validate(&registry, input)? { 0 } else { 1 }),
+		(Some("dump"), ["--json"], [input]) => dump(&registry, input)?,
+		_ => {
+			eprint!("{}", USAGE);
+			return Ok(2);
+		},
+	}
+
+	Ok(0)
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+
+	match run(&args) {
+		Ok(code) => process::exit(code),
+		Err(e) => {
+			eprintln!("meshio: {}", e);
+			process::exit(1);
+		},
+	}
+}
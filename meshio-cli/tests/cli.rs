@@ -0,0 +1,105 @@
+use std::{
+	env,
+	fs,
+	path::PathBuf,
+	process::{
+		Command,
+		Output
+	}
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/triangle.pmx");
+
+fn meshio(args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_meshio")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+	String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn info_counts_elements() {
+	let output = meshio(&["info", FIXTURE]);
+	let text = stdout(&output);
+
+	assert!(output.status.success());
+	assert!(text.contains("format: PMX"));
+	assert!(text.contains("nodes: 1, vertices: 3, faces: 1"));
+	assert!(text.contains("bones: 2, morphs: 1, animations: 0"));
+}
+
+#[test]
+fn validate_accepts_fixture() {
+	let output = meshio(&["validate", FIXTURE]);
+	assert!(output.status.success());
+	assert!(stdout(&output).contains(": ok"));
+}
+
+#[test]
+fn convert_writes_obj() {
+	let path: PathBuf = env::temp_dir().join(format!("meshio-cli-{}.obj", std::process::id()));
+	let output = meshio(&["convert", FIXTURE, path.to_str().unwrap()]);
+	let obj = fs::read_to_string(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+
+	assert!(output.status.success());
+	assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 3);
+	assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 1);
+}
+
+#[test]
+fn convert_reads_obj() {
+	let input: PathBuf = env::temp_dir().join(format!("meshio-cli-{}-in.obj", std::process::id()));
+	let output: PathBuf = env::temp_dir().join(format!("meshio-cli-{}-out.obj", std::process::id()));
+	fs::write(&input, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+
+	let status = meshio(&["convert", input.to_str().unwrap(), output.to_str().unwrap()]).status;
+	let info = meshio(&["info", input.to_str().unwrap()]);
+	let obj = fs::read_to_string(&output).unwrap();
+	fs::remove_file(&input).unwrap();
+	fs::remove_file(&output).unwrap();
+
+	assert!(status.success());
+	assert!(stdout(&info).contains("format: OBJ"));
+	assert!(stdout(&info).contains("nodes: 1, vertices: 3, faces: 1"));
+	assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 1);
+}
+
+#[test]
+fn convert_to_pmx_is_refused() {
+	let path = env::temp_dir().join(format!("meshio-cli-{}.pmx", std::process::id()));
+	let output = meshio(&["convert", FIXTURE, path.to_str().unwrap()]);
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be written"));
+	assert!(!path.exists());
+}
+
+#[test]
+fn dump_prints_json() {
+	let output = meshio(&["dump", "--json", FIXTURE]);
+	assert!(output.status.success());
+	assert!(stdout(&output).trim_start().starts_with('{'));
+}
+
+#[test]
+fn usage_on_bad_arguments() {
+	assert_eq!(meshio(&["frobnicate"]).status.code(), Some(2));
+}
+
+#[test]
+fn unknown_flags_are_refused() {
+	let path = env::temp_dir().join(format!("meshio-cli-{}-flag.obj", std::process::id()));
+
+	for args in [
+		&["convert", "--keep-axis", FIXTURE, path.to_str().unwrap()][..],
+		&["info", "--json", FIXTURE],
+		&["dump", "--json", "--keep-axes", FIXTURE],
+	].iter() {
+		let output = meshio(args);
+		assert_eq!(output.status.code(), Some(2), "{:?}", args);
+		assert!(String::from_utf8_lossy(&output.stderr).starts_with("usage: meshio"));
+	}
+	assert!(!path.exists());
+}
@@ -0,0 +1,10 @@
+#![allow(clippy::redundant_field_names)]
+
+mod names;
+mod pmx;
+
+pub use names::{
+	english_name,
+	japanese_name
+};
+pub use pmx::Pmx;
@@ -0,0 +1,128 @@
+/// Standard bone and morph names without a side prefix, as (Japanese, English) pairs
+const NAMES: &[(&str, &str)] = &[
+	// Bones
+	("全ての親", "master"),
+	("センター", "center"),
+	("グルーブ", "groove"),
+	("腰", "waist"),
+	("操作中心", "view cnt"),
+	("上半身", "upper body"),
+	("上半身2", "upper body2"),
+	("下半身", "lower body"),
+	("首", "neck"),
+	("頭", "head"),
+	("両目", "eyes"),
+	("目", "eye"),
+	("肩P", "shoulderP"),
+	("肩", "shoulder"),
+	("肩C", "shoulderC"),
+	("腕", "arm"),
+	("腕捩", "arm twist"),
+	("ひじ", "elbow"),
+	("手捩", "wrist twist"),
+	("手首", "wrist"),
+	("ダミー", "dummy"),
+	("親指０", "thumb0"),
+	("親指１", "thumb1"),
+	("親指２", "thumb2"),
+	("人指１", "fore1"),
+	("人指２", "fore2"),
+	("人指３", "fore3"),
+	("中指１", "middle1"),
+	("中指２", "middle2"),
+	("中指３", "middle3"),
+	("薬指１", "third1"),
+	("薬指２", "third2"),
+	("薬指３", "third3"),
+	("小指１", "little1"),
+	("小指２", "little2"),
+	("小指３", "little3"),
+	("足", "leg"),
+	("ひざ", "knee"),
+	("足首", "ankle"),
+	("つま先", "toe"),
+	("足ＩＫ", "leg IK"),
+	("つま先ＩＫ", "toe IK"),
+	("足IK親", "leg IKP"),
+	("腰キャンセル", "waist cancel"),
+	("足D", "legD"),
+	("ひざD", "kneeD"),
+	("足首D", "ankleD"),
+	("足先EX", "toe EX"),
+	// Morphs
+	("まばたき", "blink"),
+	("笑い", "smile"),
+	("ウィンク", "wink"),
+	("ウィンク右", "wink_R"),
+	("ウィンク２", "wink2"),
+	("ｳｨﾝｸ２右", "wink2_R"),
+	("はぅ", "close><"),
+	("なごみ", "calm"),
+	("びっくり", "surprised"),
+	("じと目", "stare"),
+	("瞳小", "pupil"),
+	("あ", "a"),
+	("い", "i"),
+	("う", "u"),
+	("え", "e"),
+	("お", "o"),
+	("にやり", "grin"),
+	("真面目", "serious"),
+	("困る", "troubled"),
+	("怒り", "angry"),
+	("上", "up"),
+	("下", "down"),
+	("前", "front"),
+	("ぺろっ", "tongue"),
+	("照れ", "blush"),
+];
+
+/// Folds full-width ASCII forms, such as `ＩＫ` and `１`, to their ASCII equivalents
+fn fold(name: &str) -> String {
+	name.chars()
+		.map(|c| match c {
+			'\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+			'\u{3000}' => ' ',
+			c => c,
+		})
+		.collect()
+}
+
+/// Returns the conventional English name of a standard MMD bone or morph
+///
+/// Bones prefixed with `左` or `右` get an `_L` or `_R` suffix. Full-width and half-width
+/// letters and digits are treated alike.
+pub fn english_name(japanese: &str) -> Option<String> {
+	let (side, base) = match japanese.chars().next() {
+		Some('左') => ("_L", &japanese['左'.len_utf8()..]),
+		Some('右') => ("_R", &japanese['右'.len_utf8()..]),
+		_ => ("", japanese),
+	};
+	let base = fold(base);
+
+	NAMES.iter()
+		.find(|(j, _)| fold(j) == base)
+		.map(|(_, e)| format!("{}{}", e, side))
+}
+
+/// Returns the standard Japanese name of an MMD bone or morph from its conventional English
+/// name, the inverse of [`english_name`]
+pub fn japanese_name(english: &str) -> Option<String> {
+	let lower = english.to_lowercase();
+
+	if let Some((j, _)) = NAMES.iter().find(|(_, e)| e.to_lowercase() == lower) {
+		return Some(j.to_string());
+	}
+
+	let (side, base) = if let Some(base) = lower.strip_suffix("_l") {
+		("左", base)
+	} else if let Some(base) = lower.strip_suffix("_r") {
+		("右", base)
+	} else {
+		return None;
+	};
+
+	NAMES.iter()
+		.find(|(_, e)| e.to_lowercase() == base)
+		.map(|(j, _)| format!("{}{}", side, j))
+}
@@ -1,6 +1,13 @@
+// The model mirrors the file, including data that has no core equivalent yet
+#![allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+
 use bitflags::bitflags;
-use bytes::BufMut;
-use std::{u8,u16};
+
+use std::{
+	collections::HashMap,
+	convert::TryFrom,
+	iter
+};
 
 use nom::{
 	call,
@@ -8,14 +15,15 @@ use nom::{
 	count,
 	do_parse,
 	Err,
+	error::ErrorKind,
 	IResult,
 	length_data,
 	map,
 	named,
 	named_args,
-	Needed,
 	number::complete::{
 		le_f32,
+		le_i8,
 		le_i16,
 		le_i32,
 		le_u8,
@@ -25,42 +33,70 @@ use nom::{
 	switch,
 	tag,
 	take,
-	value
+	value,
+	verify
 };
 
 use encoding::{
-	ByteWriter,
 	DecoderTrap,
-	EncoderTrap,
-	UTF_16LE_ENCODING
+	Encoding as _,
+	all::UTF_16LE
 };
 
 use meshio::{
 	BoundsF,
 	ColorF,
+	CoordinateSystem,
+	Face,
+	Format,
+	Importer,
 	Language,
 	le_bounds32,
+	le_rgb_f,
 	le_rgba_f,
 	le_v2f,
 	le_v3f,
 	le_v4f,
-	LocalizedStringMap
+	LocalizedStringMap,
+	MorphCategory,
+	MorphOffset,
+	Node,
+	Object,
+	physics::{
+		BodyMode,
+		ConstraintKind,
+		Limits,
+		Shape,
+		Spring
+	},
+	Position,
+	SceneF,
+	Skeleton,
+	SkinWeights,
+	Texture,
+	TextureSlot
 };
 
 use cgmath::{
+	Quaternion,
+	Rad,
+	Rotation3,
 	Vector2,
 	Vector3,
-	Vector4
+	Vector4,
+	Zero
 };
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+use crate::names::english_name;
+
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 #[repr(u8)]
 enum Encoding {
 	UTF16LE = 0,
 	UTF8 = 1,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 struct Settings {
 	encoding: Encoding,
 	uv: u8,
@@ -72,7 +108,7 @@ struct Settings {
 	rigid_body_index_size: u8,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Header {
 	version: f32,
 	settings: Settings,
@@ -80,7 +116,7 @@ struct Header {
 	comment: LocalizedStringMap,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 enum Skinning {
 	BDef1 {
 		index: i32
@@ -104,8 +140,29 @@ enum Skinning {
 		weights: [f32; 4]
 	}
 }
+impl Skinning {
+	/// Converts the deform into core weights, skipping the -1 placeholder index
+	///
+	/// SDEF and QDEF parameters beyond their linear weights have no core equivalent.
+	fn weights(&self) -> SkinWeights<f32> {
+		let (indices, weights): (&[i32], Vec<f32>) = match self {
+			Skinning::BDef1 { index } => (std::slice::from_ref(index), vec![1.0]),
+			Skinning::BDef2 { indices, weight } |
+			Skinning::SDef { indices, weight, .. } => (&indices[..], vec![*weight, 1.0 - *weight]),
+			Skinning::BDef4 { indices, weights } |
+			Skinning::QDef { indices, weights } => (&indices[..], weights.to_vec()),
+		};
+		let mut skin = SkinWeights::default();
+		
+		indices.iter().zip(weights)
+			.filter(|(&i, _)| i >= 0)
+			.for_each(|(&i, w)| skin.add(i as usize, w));
+		skin.normalize();
+		skin
+	}
+}
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Vertex {
 	position: Vector3<f32>,
 	normal: Vector3<f32>,
@@ -144,7 +201,7 @@ enum ToonReference {
 	INTERNAL = 1,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Material {
 	name: LocalizedStringMap,
 	diffuse: ColorF,
@@ -180,40 +237,39 @@ bitflags! {
 	}
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 enum TailPosition {
 	Vector3(Vector3<f32>),
 	Index(i32),
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct InheritBone {
 	index: i32,
 	weight: f32,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct LocalCoordinate {
 	x: Vector3<f32>,
 	z: Vector3<f32>,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Link {
 	bone_index: i32,
-	has_limits: bool,
-	limits: Option<Vec<BoundsF>>,
+	limits: Option<BoundsF>,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct InverseKinematic {
 	target: i32,
-	loop_count: u32,
+	loop_count: i32,
 	angle_limit: f32,
-	links: Option<Vec<Link>>,
+	links: Vec<Link>,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Bone {
 	name_local: String,
 	name_global: String,
@@ -239,7 +295,7 @@ enum Category {
 	OTHER = 4,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 #[repr(u8)]
 enum MorphType {
 	GROUP = 0,
@@ -255,7 +311,7 @@ enum MorphType {
 	IMPULSE = 10,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 enum Offset {
 	Group {
 		index: i32,
@@ -299,7 +355,7 @@ enum Offset {
 	},
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Morph {
 	name_local: String,
 	name_global: String,
@@ -323,12 +379,12 @@ enum FrameType {
 }
 
 #[derive(Clone,Debug,PartialEq,Eq)]
-enum FrameElement {
+struct FrameElement {
 	kind: FrameType,
 	index: i32,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Frame {
 	name_local: String,
 	name_global: String,
@@ -352,7 +408,7 @@ enum Physics {
 	GRAVITY_BONE = 2,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct RigidBody {
 	name_local: String,
 	name_global: String,
@@ -382,7 +438,7 @@ enum JointType {
 	HINGE = 5,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Joint {
 	name_local: String,
 	name_global: String,
@@ -429,11 +485,11 @@ struct AnchorRigidBody {
 	near_mode: u8,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct SoftBody {
 	name_local: String,
 	name_global: String,
-	shape: Shape,
+	shape: SoftShape,
 	material: i32,
 	group: u8,
 	mask: u16,
@@ -472,54 +528,69 @@ struct SoftBody {
 	vertex_pins: Vec<i32>,
 }
 
-#[derive(Clone,Debug,PartialEq,Eq)]
+#[derive(Clone,Debug,PartialEq)]
 struct Model {
 	header: Header,
 	vertices: Vec<Vertex>,
 	indices: Vec<i32>,
 	textures: Vec<String>,
 	materials: Vec<Material>,
+	bones: Vec<Bone>,
 	morphs: Vec<Morph>,
 	frames: Vec<Frame>,
 	rigid_bodies: Vec<RigidBody>,
 	joints: Vec<Joint>,
 	soft_bodies: Vec<SoftBody>,
+	/// Byte offset of the bone table, where errors in the bone hierarchy are reported
+	bone_table: usize,
 }
 
-fn index(input: &[u8], size: u8) -> IResult<&[u8],i32> {
-	if input.len() < size || size > 4 {
-		Err(Err::Incomplete(Needed::Size(size)))
-	} else {
-		match size {
-			1 => map!(input, le_i8, |x| x as i32),
-			2 => map!(input, le_i16, |x| x as i32),
-			4 => le_i32(input),
-		}
+/// Parses a bone, texture, material, morph or rigid body index stored in `size` bytes
+fn index(input: &[u8], size: u8) -> IResult<&[u8], i32> {
+	match size {
+		1 => map!(input, le_i8, i32::from),
+		2 => map!(input, le_i16, i32::from),
+		4 => le_i32(input),
+		_ => Err(Err::Failure((input, ErrorKind::Switch))),
 	}
 }
 
-fn string<'a>(input: &[u8], encoding: Encoding) -> IResult<&[u8],&'a str> {
-	match encoding {
-		Encoding::UTF16LE => map!(input, length_data!(le_u32), |s| UTF_16LE_ENCODING.decode(s, DecoderTrap::Replace)),
-		Encoding::UTF8 => map!(input, length_data!(le_u32), |s| std:str::from_utf8(s).unwrap_or_default()),
+/// Parses a vertex index, which unlike other indices is unsigned when stored in one or two bytes
+fn vertex_index(input: &[u8], size: u8) -> IResult<&[u8], i32> {
+	match size {
+		1 => map!(input, le_u8, i32::from),
+		2 => map!(input, le_u16, i32::from),
+		4 => le_i32(input),
+		_ => Err(Err::Failure((input, ErrorKind::Switch))),
 	}
 }
 
+/// Parses a length-prefixed string, replacing invalid characters
+fn string(input: &[u8], encoding: Encoding) -> IResult<&[u8], String> {
+	let (i, data) = length_data!(input, le_u32)?;
+	let text = match encoding {
+		Encoding::UTF16LE => UTF_16LE.decode(data, DecoderTrap::Replace).unwrap_or_else(|e| e.into_owned()),
+		Encoding::UTF8 => String::from_utf8_lossy(data).into_owned(),
+	};
+	
+	Ok((i, text))
+}
+
 named!(settings<Settings>,
 	do_parse!(
-		settings_count: le_u8 >>
+		settings_count: verify!(le_u8, |&n: &u8| n >= 8) >>
 		encoding: switch!(le_u8,
 			0 => value!(Encoding::UTF16LE) |
 			1 => value!(Encoding::UTF8)
 		) >>
-		uv: le_u8 >>
+		uv: verify!(le_u8, |&n: &u8| n <= 4) >>
 		vertex_index_size: le_u8 >>
 		texture_index_size: le_u8 >>
 		material_index_size: le_u8 >>
 		bone_index_size: le_u8 >>
 		morph_index_size: le_u8 >>
 		rigid_body_index_size: le_u8 >>
-		extra: take!(settings_count - 8) >>
+		take!(settings_count - 8) >>
 		
 		(Settings {
 			encoding: encoding,
@@ -547,15 +618,13 @@ named!(header<Header>,
 		(Header {
 			version: version,
 			settings: settings,
-			name_local: name_local,
-			name_global: name_global,
-			comment_local: comment_local,
-			comment_global: comment_global,
+			name: localized(&name_local, &name_global),
+			comment: localized(&comment_local, &comment_global),
 		})
 	)
 );
 
-named_args!(bdef1(input: &[u8], index_size: u8)<Skinning>,
+named_args!(bdef1(index_size: u8)<Skinning>,
 	do_parse!(
 		index: call!(index, index_size) >>
 		(Skinning::BDef1 {
@@ -564,21 +633,42 @@ named_args!(bdef1(input: &[u8], index_size: u8)<Skinning>,
 	)
 );
 
-named_args!(bdef2(input: &[u8], index_size: u8)<Skinning>,
+named_args!(bdef2(index_size: u8)<Skinning>,
 	do_parse!(
-		indices: count!(call!(index, index_size), 2) >>
+		a: call!(index, index_size) >>
+		b: call!(index, index_size) >>
 		weight: le_f32 >>
 		(Skinning::BDef2 {
-			indices: indices,
+			indices: [a, b],
 			weight: weight,
 		})
 	)
 );
 
-named_args!(bdef4(input: &[u8], index_size: u8)<Skinning>,
+named_args!(indices4(index_size: u8)<[i32; 4]>,
+	do_parse!(
+		a: call!(index, index_size) >>
+		b: call!(index, index_size) >>
+		c: call!(index, index_size) >>
+		d: call!(index, index_size) >>
+		([a, b, c, d])
+	)
+);
+
+named!(weights4<[f32; 4]>,
+	do_parse!(
+		a: le_f32 >>
+		b: le_f32 >>
+		c: le_f32 >>
+		d: le_f32 >>
+		([a, b, c, d])
+	)
+);
+
+named_args!(bdef4(index_size: u8)<Skinning>,
 	do_parse!(
-		indices: count_fixed!(&[u32], call!(index, index_size), 4) >>
-		weights: count_fixed!(&[f32], le_f32, 4) >>
+		indices: call!(indices4, index_size) >>
+		weights: weights4 >>
 		(Skinning::BDef4 {
 			indices: indices,
 			weights: weights,
@@ -586,25 +676,27 @@ named_args!(bdef4(input: &[u8], index_size: u8)<Skinning>,
 	)
 );
 
-named_args!(sdef(input: &[u8], index_size: u8)<Skinning>,
+named_args!(sdef(index_size: u8)<Skinning>,
 	do_parse!(
-		indices: count_fixed!(&[u32], call!(index, index_size), 2) >>
+		a: call!(index, index_size) >>
+		b: call!(index, index_size) >>
 		weight: le_f32 >>
 		c: le_v3f >>
-		r: count_fixed!(&[Vector3<f32>], le_v3f, 2) >>
+		r0: le_v3f >>
+		r1: le_v3f >>
 		(Skinning::SDef {
-			indices: indices,
+			indices: [a, b],
 			weight: weight,
 			c: c,
-			r: r,
+			r: [r0, r1],
 		})
 	)
 );
 
-named_args!(qdef(input: &[u8], index_size: u8)<Skinning>,
+named_args!(qdef(index_size: u8)<Skinning>,
 	do_parse!(
-		indices: count_fixed!(&[u32], call!(index, index_size), 4) >>
-		weights: count_fixed!(&[f32], le_f32, 4) >>
+		indices: call!(indices4, index_size) >>
+		weights: weights4 >>
 		(Skinning::QDef {
 			indices: indices,
 			weights: weights,
@@ -612,19 +704,19 @@ named_args!(qdef(input: &[u8], index_size: u8)<Skinning>,
 	)
 );
 
-named_args!(vertex(input: &[u8], index_size: u8)<Vertex>,
+named_args!(vertex(settings: Settings)<Vertex>,
 	do_parse!(
 		position: le_v3f >>
 		normal: le_v3f >>
 		uv: le_v2f >>
-		uva: count!(le_v4f, settings.uv) >>
+		uva: count!(le_v4f, settings.uv as usize) >>
 		skinning: switch!(le_u8,
-			0 => call!(bdef1, index_size) |
-			1 => call!(bdef2, index_size) |
-			2 => call!(bdef4, index_size) |
-			3 => call!(sdef, index_size) |
-			4 => call!(qdef, index_size)
-		)
+			0 => call!(bdef1, settings.bone_index_size) |
+			1 => call!(bdef2, settings.bone_index_size) |
+			2 => call!(bdef4, settings.bone_index_size) |
+			3 => call!(sdef, settings.bone_index_size) |
+			4 => call!(qdef, settings.bone_index_size)
+		) >>
 		edge: le_f32 >>
 		(Vertex {
 			position: position,
@@ -637,7 +729,7 @@ named_args!(vertex(input: &[u8], index_size: u8)<Vertex>,
 	)
 );
 
-named_args!(material(input: &[u8], settings: &Settings)<Material>,
+named_args!(material(settings: Settings)<Material>,
 	do_parse!(
 		name_local: call!(string, settings.encoding) >>
 		name_global: call!(string, settings.encoding) >>
@@ -660,50 +752,40 @@ named_args!(material(input: &[u8], settings: &Settings)<Material>,
 			0 => value!(ToonReference::TEXTURE) |
 			1 => value!(ToonReference::INTERNAL)
 		) >>
-		toon_texture: cond!(toon_reference == 0, le_u8) >>
-		toon_internal: cond!(toon_texture.is_none(), call!(index, index_size)) >>
+		toon_texture: cond!(toon_reference == ToonReference::TEXTURE, call!(index, settings.texture_index_size)) >>
+		toon_internal: cond!(toon_reference == ToonReference::INTERNAL, le_u8) >>
 		comment: call!(string, settings.encoding) >>
 		surface_count: le_u32 >>
 		(Material {
-			name_local: name_local,
-			name_global: name_global,
+			name: localized(&name_local, &name_global),
 			diffuse: diffuse,
 			specular: specular,
 			specularity: specularity,
 			ambient: ambient,
-			flags: MaterialFlags::from_bytes_truncate(flags),
+			flags: MaterialFlags::from_bits_truncate(flags),
 			edge_color: edge_color,
 			edge_scale: edge_scale,
 			texture: texture,
 			environment: environment,
 			environment_blend_mode: environment_blend_mode,
 			toon_reference: toon_reference,
-			toon: if toon_texture.is_some() {
-				toon_texture.unwrap() as i32
-			} else {
-				toon_internal.unwrap()
-			},
+			toon: toon_texture.or_else(|| toon_internal.map(i32::from)).unwrap_or(-1),
 			comment: comment,
 			surface_count: surface_count,
 		})
 	)
 );
 
-named!(tail_position<TailPosition>,
-	do_parse!(
-		v3: le_v3f >>
-		(TailPosition::Vector3(v3))
-	)
-);
-
-named_args!(tail_position_indexed(input: &[u8], index_size: u8)<TailPosition>,
-	do_parse!(
-		index: call!(index, index_size) >>
-		(TailPosition::Index(index))
-	)
-);
+/// Parses the tail, which is an offset unless the flags say it is a bone index
+fn tail_position(input: &[u8], flags: BoneFlags, index_size: u8) -> IResult<&[u8], TailPosition> {
+	if flags.contains(BoneFlags::INDEXED_TAIL_POSITION) {
+		map!(input, call!(index, index_size), TailPosition::Index)
+	} else {
+		map!(input, le_v3f, TailPosition::Vector3)
+	}
+}
 
-named_args!(inherit_bone(input: &[u8], index_size: u8)<InheritBone>,
+named_args!(inherit_bone(index_size: u8)<InheritBone>,
 	do_parse!(
 		index: call!(index, index_size) >>
 		weight: le_f32 >>
@@ -725,27 +807,25 @@ named!(local_coordinate<LocalCoordinate>,
 	)
 );
 
-named_args!(link(input: &[u8], index_size: u8)<Link>,
+named_args!(link(index_size: u8)<Link>,
 	do_parse!(
 		bone_index: call!(index, index_size) >>
 		has_limits: le_u8 >>
-		limit_count: le_u32 >>
-		limits: count!(le_bounds32, limit_count) >>
+		limits: cond!(has_limits != 0, le_bounds32) >>
 		(Link {
 			bone_index: bone_index,
-			has_limits: has_limits as bool,
 			limits: limits,
 		})
 	)
 );
 
-named_args!(ik(input: &[u8], index_size: u8)<InverseKinematic>,
+named_args!(ik(index_size: u8)<InverseKinematic>,
 	do_parse!(
 		target: call!(index, index_size) >>
-		loop_count: le_u32 >>
+		loop_count: le_i32 >>
 		angle_limit: le_f32 >>
-		link_count: le_u32 >>
-		links: count!(link, link_count) >>
+		link_count: le_i32 >>
+		links: count!(call!(link, index_size), link_count.max(0) as usize) >>
 		(InverseKinematic {
 			target: target,
 			loop_count: loop_count,
@@ -755,33 +835,29 @@ named_args!(ik(input: &[u8], index_size: u8)<InverseKinematic>,
 	)
 );
 
-named_args!(bone(input: &[u8], settings: &Settings)<Bone>,
+named_args!(bone(settings: Settings)<Bone>,
 	do_parse!(
 		name_local: call!(string, settings.encoding) >>
 		name_global: call!(string, settings.encoding) >>
 		position: le_v3f >>
-		parent: le_i32 >>
+		parent: call!(index, settings.bone_index_size) >>
 		layer: le_i32 >>
-		flags: le_u16 >>
-		tail_position: cond!((flags & 1) == 0, tail_position) >>
-		tail_position_indexed: cond!((flags & 1) != 0, call!(tail_position_indexed, settings.bone_index_size)) >>
-		inherit_bone: cond!((flags & (256 | 512)) != 0, call!(inherit_bone, settings.bone_index_size)) >>
-		fixed_axis: cond!((flags & 1024) != 0, le_v3f) >>
-		local_coordinate: cond!((flags & 2048) != 0, local_coordinate) >>
-		key: cond!((flags & 8192) != 0, le_i32) >>
-		ik: cond!((flags & 32) != 0, call!(ik, settings.bone_index_size)) >>
+		flags: map!(le_u16, BoneFlags::from_bits_truncate) >>
+		tail_position: call!(tail_position, flags, settings.bone_index_size) >>
+		inherit_bone: cond!(flags.intersects(BoneFlags::INHERIT_ROTATION | BoneFlags::INHERIT_TRANSLATION),
+			call!(inherit_bone, settings.bone_index_size)) >>
+		fixed_axis: cond!(flags.contains(BoneFlags::FIXED_AXIS), le_v3f) >>
+		local_coordinate: cond!(flags.contains(BoneFlags::LOCAL_COORDINATE), local_coordinate) >>
+		key: cond!(flags.contains(BoneFlags::EXTERNAL_PARENT_DEFORM), le_i32) >>
+		ik: cond!(flags.contains(BoneFlags::USE_IK), call!(ik, settings.bone_index_size)) >>
 		(Bone {
 			name_local: name_local,
 			name_global: name_global,
 			position: position,
 			parent: parent,
 			layer: layer,
-			flags: BoneFlags::from_bytes_truncate(flags),
-			tail_position: if tail_position.is_some() {
-				tail_position.unwrap()
-			} else {
-				tail_position_indexed.unwrap()
-			},
+			flags: flags,
+			tail_position: tail_position,
 			inherit_bone: inherit_bone,
 			fixed_axis: fixed_axis,
 			local_coordinate: local_coordinate,
@@ -791,39 +867,614 @@ named_args!(bone(input: &[u8], settings: &Settings)<Bone>,
 	)
 );
 
-named!(pub(crate) model<Model>,
+/// Parses one offset of a morph, whose layout depends on the kind of morph
+fn offset(input: &[u8], kind: MorphType, settings: Settings) -> IResult<&[u8], Offset> {
+	match kind {
+		MorphType::GROUP => do_parse!(input,
+			index: call!(index, settings.morph_index_size) >>
+			weight: le_f32 >>
+			(Offset::Group {
+				index: index,
+				weight: weight,
+			})
+		),
+		MorphType::VERTEX => do_parse!(input,
+			index: call!(vertex_index, settings.vertex_index_size) >>
+			translation: le_v3f >>
+			(Offset::Vertex {
+				index: index,
+				translation: translation,
+			})
+		),
+		MorphType::BONE => do_parse!(input,
+			index: call!(index, settings.bone_index_size) >>
+			translation: le_v3f >>
+			rotation: le_v4f >>
+			(Offset::Bone {
+				index: index,
+				translation: translation,
+				rotation: rotation,
+			})
+		),
+		MorphType::MATERIAL => do_parse!(input,
+			index: call!(index, settings.material_index_size) >>
+			operation: le_u8 >>
+			diffuse: le_rgba_f >>
+			specular: le_rgb_f >>
+			specularity: le_f32 >>
+			ambient: le_rgb_f >>
+			edge_color: le_rgba_f >>
+			edge_scale: le_f32 >>
+			texture_tint: le_rgba_f >>
+			environment_tint: le_rgba_f >>
+			toon_tint: le_rgba_f >>
+			(Offset::Material {
+				index: index,
+				operation: operation,
+				diffuse: diffuse,
+				specular: specular,
+				specularity: specularity,
+				ambient: ambient,
+				edge_color: edge_color,
+				edge_scale: edge_scale,
+				texture_tint: texture_tint,
+				environment_tint: environment_tint,
+				toon_tint: toon_tint,
+			})
+		),
+		MorphType::FLIP => do_parse!(input,
+			index: call!(index, settings.morph_index_size) >>
+			weight: le_f32 >>
+			(Offset::Flip {
+				index: index,
+				weight: weight,
+			})
+		),
+		MorphType::IMPULSE => do_parse!(input,
+			index: call!(index, settings.rigid_body_index_size) >>
+			is_local: le_u8 >>
+			speed: le_v3f >>
+			torque: le_v3f >>
+			(Offset::Impulse {
+				index: index,
+				is_local: is_local != 0,
+				speed: speed,
+				torque: torque,
+			})
+		),
+		_ => do_parse!(input,
+			index: call!(vertex_index, settings.vertex_index_size) >>
+			offsets: le_v4f >>
+			(Offset::Uv {
+				index: index,
+				offsets: offsets,
+			})
+		),
+	}
+}
+
+named_args!(morph(settings: Settings)<Morph>,
 	do_parse!(
-		header: header >>
-		vertex_count: le_u32 >>
-		vertices: count!(call!(vertex, header.settings.bone_index_size), vertex_count) >>
-		index_count: le_u32 >>
-		indices: count!(call!(index, header.settings.vertex_index_size), index_count) >>
-		texture_count: le_u32 >>
-		textures: count!(call!(string, header.settings.encoding), texture_count) >>
-		material_count: le_u32 >>
-		materials: count!(call!(material, &header.settings), material_count) >>
-		bone_count: le_u32 >>
-		bones: count!(call!(bone, &header.settings), bone_count) >>
+		name_local: call!(string, settings.encoding) >>
+		name_global: call!(string, settings.encoding) >>
+		category: switch!(le_u8,
+			0 => value!(Category::RESERVED) |
+			1 => value!(Category::EYEBROW) |
+			2 => value!(Category::EYE) |
+			3 => value!(Category::MOUTH) |
+			4 => value!(Category::OTHER)
+		) >>
+		kind: switch!(le_u8,
+			0 => value!(MorphType::GROUP) |
+			1 => value!(MorphType::VERTEX) |
+			2 => value!(MorphType::BONE) |
+			3 => value!(MorphType::UV) |
+			4 => value!(MorphType::ADDITIONAL_UV1) |
+			5 => value!(MorphType::ADDITIONAL_UV2) |
+			6 => value!(MorphType::ADDITIONAL_UV3) |
+			7 => value!(MorphType::ADDITIONAL_UV4) |
+			8 => value!(MorphType::MATERIAL) |
+			9 => value!(MorphType::FLIP) |
+			10 => value!(MorphType::IMPULSE)
+		) >>
+		offset_count: le_i32 >>
+		offsets: count!(call!(offset, kind, settings), offset_count.max(0) as usize) >>
+		(Morph {
+			name_local: name_local,
+			name_global: name_global,
+			category: category,
+			kind: kind,
+			offsets: offsets,
+		})
+	)
+);
+
+named_args!(frame_element(settings: Settings)<FrameElement>,
+	do_parse!(
+		kind: switch!(le_u8,
+			0 => value!(FrameType::BONE) |
+			1 => value!(FrameType::MORPH)
+		) >>
+		index: call!(index, match kind {
+			FrameType::BONE => settings.bone_index_size,
+			FrameType::MORPH => settings.morph_index_size,
+		}) >>
+		(FrameElement {
+			kind: kind,
+			index: index,
+		})
+	)
+);
+
+named_args!(frame(settings: Settings)<Frame>,
+	do_parse!(
+		name_local: call!(string, settings.encoding) >>
+		name_global: call!(string, settings.encoding) >>
+		flag: switch!(le_u8,
+			0 => value!(FrameFlag::NORMAL) |
+			1 => value!(FrameFlag::SPECIAL)
+		) >>
+		element_count: le_i32 >>
+		elements: count!(call!(frame_element, settings), element_count.max(0) as usize) >>
+		(Frame {
+			name_local: name_local,
+			name_global: name_global,
+			flag: flag,
+			elements: elements,
+		})
+	)
 );
 
-fn determine_index_size(count: u32) -> u8 {
-	if count < (u8::max_value() as u32) { return 1 }
-	if count < ((u16::max_value() as u32) - 1) { return 2 }
-	4
+named_args!(rigid_body(settings: Settings)<RigidBody>,
+	do_parse!(
+		name_local: call!(string, settings.encoding) >>
+		name_global: call!(string, settings.encoding) >>
+		bone: call!(index, settings.bone_index_size) >>
+		group: le_u8 >>
+		mask: le_u16 >>
+		shape: switch!(le_u8,
+			0 => value!(RigidShape::SPHERE) |
+			1 => value!(RigidShape::BOX) |
+			2 => value!(RigidShape::CAPSULE)
+		) >>
+		scale: le_v3f >>
+		position: le_v3f >>
+		rotation: le_v3f >>
+		mass: le_f32 >>
+		attenuation: le_f32 >>
+		damping: le_f32 >>
+		repulsion: le_f32 >>
+		friction: le_f32 >>
+		mode: switch!(le_u8,
+			0 => value!(Physics::FOLLOW_BONE) |
+			1 => value!(Physics::GRAVITY) |
+			2 => value!(Physics::GRAVITY_BONE)
+		) >>
+		(RigidBody {
+			name_local: name_local,
+			name_global: name_global,
+			bone: bone,
+			group: group,
+			mask: mask,
+			shape: shape,
+			scale: scale,
+			position: position,
+			rotation: rotation,
+			mass: mass,
+			attenuation: attenuation,
+			damping: damping,
+			repulsion: repulsion,
+			friction: friction,
+			mode: mode,
+		})
+	)
+);
+
+named_args!(joint(settings: Settings)<Joint>,
+	do_parse!(
+		name_local: call!(string, settings.encoding) >>
+		name_global: call!(string, settings.encoding) >>
+		kind: switch!(le_u8,
+			0 => value!(JointType::SPRING_6DOF) |
+			1 => value!(JointType::SIX_DOF) |
+			2 => value!(JointType::P2P) |
+			3 => value!(JointType::CONE_TWIST) |
+			4 => value!(JointType::SLIDER) |
+			5 => value!(JointType::HINGE)
+		) >>
+		a: call!(index, settings.rigid_body_index_size) >>
+		b: call!(index, settings.rigid_body_index_size) >>
+		position: le_v3f >>
+		rotation: le_v3f >>
+		position_min: le_v3f >>
+		position_max: le_v3f >>
+		rotation_min: le_v3f >>
+		rotation_max: le_v3f >>
+		position_spring: le_v3f >>
+		rotation_spring: le_v3f >>
+		(Joint {
+			name_local: name_local,
+			name_global: name_global,
+			kind: kind,
+			indices: [a, b],
+			position: position,
+			rotation: rotation,
+			position_min: position_min,
+			position_max: position_max,
+			rotation_min: rotation_min,
+			rotation_max: rotation_max,
+			position_spring: position_spring,
+			rotation_spring: rotation_spring,
+		})
+	)
+);
+
+/// Parses a length-prefixed table, attributing any failure to `context`
+fn table<'a, T, F>(input: &'a [u8], whole: &[u8], context: &str, item: F) -> meshio::Result<(&'a [u8], Vec<T>)>
+	where F: Fn(&'a [u8]) -> IResult<&'a [u8], T> {
+	let parse = |i: &'a [u8]| -> IResult<&'a [u8], Vec<T>> {
+		let (i, n) = le_u32(i)?;
+		count!(i, item, n as usize)
+	};
+	
+	parse(input).map_err(|e| meshio::Error::parse(Format::Pmx, context, whole, e))
 }
 
-pub fn to_pmx(scn: &Scene, enc: Encoding) -> Result<()> {
-	let mut buf = vec![];
+fn model(input: &[u8]) -> meshio::Result<Model> {
+	let (i, header) = header(input).map_err(|e| meshio::Error::parse(Format::Pmx, "header", input, e))?;
 	
-	buf.put(b"PMX ");
-	buf.put_f32_le(2.0); // version
-	buf.put_u8(8); // settings count
-	buf.put_u8(enc as u8);
-	// todo: index size data
+	if header.version != 2.0 && header.version != 2.1 {
+		return Err(meshio::Error::UnsupportedVersion {
+			format: Format::Pmx,
+			version: header.version.to_string(),
+		});
+	}
 	
-	if enc == UTF16LE {
-		
-		UTF_16LE_ENCODING.encode_to(&scn.name.text, EncoderTrap::Replace);
+	let settings = header.settings;
+	let (i, vertices) = table(i, input, "vertex table", |i| vertex(i, settings))?;
+	let (i, indices) = table(i, input, "index table", |i| vertex_index(i, settings.vertex_index_size))?;
+	let (i, textures) = table(i, input, "texture table", |i| string(i, settings.encoding))?;
+	let (i, materials) = table(i, input, "material table", |i| material(i, settings))?;
+	let bone_table = input.len() - i.len();
+	let (i, bones) = table(i, input, "bone table", |i| bone(i, settings))?;
+	let (i, morphs) = table(i, input, "morph table", |i| morph(i, settings))?;
+	let (i, frames) = table(i, input, "display frame table", |i| frame(i, settings))?;
+	let (i, rigid_bodies) = table(i, input, "rigid body table", |i| rigid_body(i, settings))?;
+	let (_, joints) = table(i, input, "joint table", |i| joint(i, settings))?;
+	
+	Ok(Model {
+		vertices: vertices,
+		indices: indices,
+		textures: textures,
+		materials: materials,
+		bones: bones,
+		morphs: morphs,
+		frames: frames,
+		rigid_bodies: rigid_bodies,
+		joints: joints,
+		soft_bodies: Vec::new(),
+		bone_table: bone_table,
+		header: header,
+	})
+}
+
+/// Importer for PMX (Polygon Model eXtended) models
+///
+/// The base texture coordinates become the set named `uv`. Each additional UV is a vec4, so it
+/// becomes two sets named `uvaN.xy` and `uvaN.zw`, in that order. Writing PMX is not supported
+/// yet, so the format has no exporter.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct Pmx;
+
+impl Importer for Pmx {
+	fn format(&self) -> Format {
+		Format::Pmx
+	}
+	
+	fn import(&self, input: &[u8]) -> meshio::Result<SceneF> {
+		SceneF::try_from(model(input)?)
+	}
+}
+
+/// Builds a name map from PMX's Japanese local and English global names
+fn localized(local: &str, global: &str) -> LocalizedStringMap {
+	let mut name = LocalizedStringMap::new();
+	
+	if !local.is_empty() { name.insert(Language::Japanese, local.to_owned()); }
+	if !global.is_empty() { name.insert(Language::English, global.to_owned()); }
+	name
+}
+
+/// Builds a bone or morph name map, giving standard names without a global name their
+/// conventional English name
+///
+/// Only bones and morphs follow the standard names, so a material called 目 stays unnamed in
+/// English rather than becoming "eye".
+fn standard_name(local: &str, global: &str) -> LocalizedStringMap {
+	let mut name = localized(local, global);
+	
+	if global.is_empty() {
+		if let Some(english) = english_name(local) {
+			name.insert(Language::English, english);
+		}
+	}
+	name
+}
+
+/// Converts PMX bones, whose positions are in model space, into a skeleton and a table that
+/// maps PMX bone indices to joint indices
+///
+/// Bones whose parents form a cycle make the model corrupt.
+fn skeleton(bones: &[Bone], offset: usize) -> meshio::Result<(Skeleton<f32>, Vec<usize>)> {
+	let parent = |b: &Bone| if b.parent >= 0 && (b.parent as usize) < bones.len() {
+		Some(b.parent as usize)
 	} else {
+		None
+	};
+	let joints = bones.iter().map(|b| {
+		let mut joint = meshio::Joint::new(standard_name(&b.name_local, &b.name_global));
+		
+		joint.parent = parent(b);
+		joint.translation = match joint.parent {
+			Some(p) => b.position - bones[p].position,
+			None => b.position,
+		};
+		joint
+	}).collect();
+	
+	let (mut skeleton, remap) = Skeleton::from_unordered(joints).map_err(|e| meshio::Error::Corrupt {
+		format: Format::Pmx,
+		context: "bone table".to_owned(),
+		position: Position::Offset(offset),
+		cause: e.to_string(),
+	})?;
+	
+	skeleton.compute_inverse_bind();
+	Ok((skeleton, remap))
+}
+
+/// Creates a node from a run of triangle indices, keeping only the vertices they reference
+///
+/// Also returns the map from PMX vertex indices to indices within the node.
+fn node(name: LocalizedStringMap, vertices: &[meshio::Vertex<f32, f32>], indices: &[i32],
+	material: Option<usize>, uv_names: &[String]) -> (Node<f32, f32>, HashMap<i32, u32>) {
+	let mut remap = HashMap::new();
+	let mut used = Vec::new();
+	let mut index = |i: i32| *remap.entry(i).or_insert_with(|| {
+		used.push(vertices[i as usize].clone());
+		(used.len() - 1) as u32
+	});
+	let faces = indices.chunks(3)
+		.filter(|f| f.len() == 3 && f.iter().all(|&i| i >= 0 && (i as usize) < vertices.len()))
+		.map(|f| Face::Triangle(Vector3::new(index(f[0]), index(f[1]), index(f[2]))))
+		.collect();
+	
+	(Node {
+		name: name,
+		vertices: used,
+		faces: faces,
+		material: material,
+		uv_names: uv_names.to_vec(),
+		color_names: Vec::new(),
+	}, remap)
+}
+
+impl Morph {
+	/// Converts vertex and UV morphs; other kinds drive bones, materials or physics and have
+	/// no core equivalent
+	///
+	/// Additional UV morphs displace the `uvaN.xy` set of their additional UV. Their `.zw`
+	/// offsets are dropped, as is everything past `.xy` of base UV morphs. `copies` lists every
+	/// (node, vertex) pair each PMX vertex was copied to.
+	fn to_core(&self, copies: &[Vec<(usize, usize)>]) -> Option<meshio::Morph<f32>> {
+		let uv_set = match self.kind {
+			MorphType::VERTEX | MorphType::UV => 0,
+			MorphType::ADDITIONAL_UV1 => 1,
+			MorphType::ADDITIONAL_UV2 => 3,
+			MorphType::ADDITIONAL_UV3 => 5,
+			MorphType::ADDITIONAL_UV4 => 7,
+			_ => return None,
+		};
+		
+		let category = match self.category {
+			Category::EYEBROW => MorphCategory::Eyebrow,
+			Category::EYE => MorphCategory::Eye,
+			Category::MOUTH => MorphCategory::Mouth,
+			_ => MorphCategory::Other,
+		};
+		let mut offsets = Vec::new();
+		
+		for offset in self.offsets.iter() {
+			let (index, position, uv) = match offset {
+				Offset::Vertex { index, translation } => (*index, *translation, Vector2::zero()),
+				Offset::Uv { index, offsets } => (*index, Vector3::zero(), offsets.truncate().truncate()),
+				_ => continue,
+			};
+			let targets = match copies.get(index as usize) {
+				Some(t) if index >= 0 => t,
+				_ => continue,
+			};
+			
+			offsets.extend(targets.iter().map(|&(node, vertex)| MorphOffset {
+				node: node,
+				vertex: vertex,
+				position: position,
+				normal: Vector3::zero(),
+				uv: uv,
+			}));
+		}
+		
+		Some(meshio::Morph {
+			name: standard_name(&self.name_local, &self.name_global),
+			category: category,
+			uv_set: uv_set,
+			offsets: offsets,
+		})
+	}
+}
+
+impl Material {
+	fn to_core(&self, textures: &[String]) -> meshio::Material {
+		let texture = |i: i32| textures.get(i as usize).filter(|_| i >= 0).map(|p| Texture::new(p));
+		let mut core = meshio::Material {
+			name: self.name.clone(),
+			diffuse: self.diffuse.to_linear(),
+			specular: self.specular.to_linear(),
+			ambient: self.ambient.to_linear(),
+			opacity: self.diffuse.alpha,
+			shininess: self.specularity,
+			..Default::default()
+		};
+		let toon = match self.toon_reference {
+			ToonReference::TEXTURE => texture(self.toon),
+			ToonReference::INTERNAL => Some(Texture::new(&format!("toon{:02}.bmp", self.toon + 1))),
+		};
+		
+		if let Some(t) = texture(self.texture) { core.textures.insert(TextureSlot::Diffuse, t); }
+		if let Some(t) = texture(self.environment) { core.textures.insert(TextureSlot::Environment, t); }
+		if let Some(t) = toon { core.textures.insert(TextureSlot::Toon, t); }
+		core
+	}
+}
+
+/// Converts MMD's Euler angles, applied in Y, X, Z order, into a rotation
+fn euler(angles: Vector3<f32>) -> Quaternion<f32> {
+	Quaternion::from_angle_y(Rad(angles.y))
+		* Quaternion::from_angle_x(Rad(angles.x))
+		* Quaternion::from_angle_z(Rad(angles.z))
+}
+
+impl RigidBody {
+	/// Converts the body, placing it relative to its PMX bone, whose index is kept for the caller
+	/// to remap
+	fn to_core(&self, bones: &[Bone]) -> meshio::physics::RigidBody<f32> {
+		let shape = match self.shape {
+			RigidShape::SPHERE => Shape::Sphere { radius: self.scale.x },
+			RigidShape::BOX => Shape::Box { half_extents: self.scale },
+			RigidShape::CAPSULE => Shape::Capsule { radius: self.scale.x, height: self.scale.y },
+		};
+		let bone = bones.get(self.bone as usize).filter(|_| self.bone >= 0);
+		let mut body = meshio::physics::RigidBody::new(localized(&self.name_local, &self.name_global), shape);
+		
+		body.joint = bone.map(|_| self.bone as usize);
+		body.transform.translation = match bone {
+			Some(b) => self.position - b.position,
+			None => self.position,
+		};
+		body.transform.rotation = euler(self.rotation);
+		body.mode = match self.mode {
+			Physics::FOLLOW_BONE => BodyMode::Kinematic,
+			Physics::GRAVITY => BodyMode::Dynamic,
+			Physics::GRAVITY_BONE => BodyMode::DynamicRotation,
+		};
+		body.mass = self.mass;
+		body.linear_damping = self.attenuation;
+		body.angular_damping = self.damping;
+		body.restitution = self.repulsion;
+		body.friction = self.friction;
+		// PMX stores one group index and the groups a body does not collide with
+		body.group = 1 << (self.group & 15);
+		body.mask = !(self.mask as u32) & 0xffff;
+		body
+	}
+}
+
+impl Joint {
+	/// Converts the joint into a constraint, or `None` if it does not connect two bodies
+	fn to_core(&self, body_count: usize) -> Option<meshio::physics::Constraint<f32>> {
+		let valid = |i: i32| if i >= 0 && (i as usize) < body_count { Some(i as usize) } else { None };
+		let bodies = [valid(self.indices[0])?, valid(self.indices[1])?];
+		let kind = match self.kind {
+			JointType::SPRING_6DOF | JointType::SIX_DOF => ConstraintKind::SixDof,
+			JointType::P2P => ConstraintKind::Point,
+			JointType::CONE_TWIST => ConstraintKind::ConeTwist,
+			JointType::SLIDER => ConstraintKind::Slider,
+			JointType::HINGE => ConstraintKind::Hinge,
+		};
+		let spring = |stiffness: Vector3<f32>| if stiffness != Vector3::zero() {
+			Some(Spring { stiffness: stiffness, damping: Vector3::zero() })
+		} else {
+			None
+		};
+		let mut constraint = meshio::physics::Constraint::new(localized(&self.name_local, &self.name_global), kind, bodies);
+		
+		constraint.transform.translation = self.position;
+		constraint.transform.rotation = euler(self.rotation);
+		constraint.linear = Limits { lower: self.position_min, upper: self.position_max };
+		constraint.angular = Limits { lower: self.rotation_min, upper: self.rotation_max };
+		constraint.linear_spring = spring(self.position_spring);
+		constraint.angular_spring = spring(self.rotation_spring);
+		Some(constraint)
+	}
+}
+
+impl TryFrom<Model> for SceneF {
+	type Error = meshio::Error;
+	
+	fn try_from(model: Model) -> meshio::Result<SceneF> {
+		let (skeleton, remap) = skeleton(&model.bones, model.bone_table)?;
+		let vertices: Vec<meshio::Vertex<f32, f32>> = model.vertices.iter().map(|v| {
+			let mut weights = v.skinning.weights();
+			
+			weights.remap(&remap);
+			meshio::Vertex {
+				position: v.position,
+				normals: [v.normal, Vector3::zero()],
+				tangent: Vector4::zero(),
+				uvs: iter::once(v.uv)
+					.chain(v.uva.iter().flat_map(|uva| vec![Vector2::new(uva.x, uva.y), Vector2::new(uva.z, uva.w)]))
+					.collect(),
+				colors: Vec::new(),
+				weights: weights,
+			}
+		}).collect();
+		
+		// Each additional UV is a vec4, kept as one set for .xy followed by one for .zw
+		let uv_names: Vec<String> = iter::once("uv".to_owned())
+			.chain((1..=model.header.settings.uv).flat_map(|i| vec![format!("uva{}.xy", i), format!("uva{}.zw", i)]))
+			.collect();
+		
+		// Materials own consecutive runs of the index buffer, in order
+		let mut nodes = Vec::new();
+		let mut start = 0;
+		
+		let mut copies = vec![Vec::new(); vertices.len()];
+		let mut add = |(n, remap): (Node<f32, f32>, HashMap<i32, u32>)| {
+			remap.iter().for_each(|(&v, &i)| copies[v as usize].push((nodes.len(), i as usize)));
+			nodes.push(n);
+		};
+		
+		for (i, material) in model.materials.iter().enumerate() {
+			let end = (start + material.surface_count as usize).min(model.indices.len());
+			add(node(material.name.clone(), &vertices, &model.indices[start..end], Some(i), &uv_names));
+			start = end;
+		}
+		if start < model.indices.len() {
+			add(node(model.header.name.clone(), &vertices, &model.indices[start..], None, &uv_names));
+		}
+		
+		let mut physics = meshio::physics::Physics {
+			bodies: model.rigid_bodies.iter().map(|b| b.to_core(&model.bones)).collect(),
+			constraints: model.joints.iter().filter_map(|j| j.to_core(model.rigid_bodies.len())).collect(),
+		};
+		
+		physics.remap(&remap);
+		
+		Ok(SceneF {
+			name: model.header.name.clone(),
+			objects: vec![Object {
+				name: model.header.name.clone(),
+				nodes: nodes,
+				skeleton: Some(skeleton),
+				animations: Vec::new(),
+				morphs: model.morphs.iter().filter_map(|m| m.to_core(&copies)).collect(),
+				physics: physics,
+			}],
+			materials: model.materials.iter().map(|m| m.to_core(&model.textures)).collect(),
+			nodes: Vec::new(),
+			lights: Vec::new(),
+			cameras: Vec::new(),
+			coordinate_system: CoordinateSystem::MMD,
+		})
 	}
 }
@@ -0,0 +1,29 @@
+use meshio_mmd::{
+	english_name,
+	japanese_name
+};
+
+#[test]
+fn english_names_of_standard_bones_and_morphs() {
+	assert_eq!(english_name("センター").as_deref(), Some("center"));
+	assert_eq!(english_name("まばたき").as_deref(), Some("blink"));
+	assert_eq!(english_name("左腕").as_deref(), Some("arm_L"));
+	assert_eq!(english_name("右足ＩＫ").as_deref(), Some("leg IK_R"));
+	// Half-width forms match the full-width table entries
+	assert_eq!(english_name("左足IK").as_deref(), Some("leg IK_L"));
+	assert_eq!(english_name("親指1").as_deref(), Some("thumb1"));
+	assert_eq!(english_name("尻尾"), None);
+	assert_eq!(english_name(""), None);
+}
+
+#[test]
+fn japanese_names_are_the_inverse() {
+	assert_eq!(japanese_name("center").as_deref(), Some("センター"));
+	assert_eq!(japanese_name("Upper Body").as_deref(), Some("上半身"));
+	assert_eq!(japanese_name("arm_L").as_deref(), Some("左腕"));
+	assert_eq!(japanese_name("leg IK_r").as_deref(), Some("右足ＩＫ"));
+	// Names that end in a side suffix themselves are found before stripping it
+	assert_eq!(japanese_name("wink_R").as_deref(), Some("ウィンク右"));
+	assert_eq!(japanese_name("tail"), None);
+	assert_eq!(japanese_name("tail_L"), None);
+}
@@ -0,0 +1,214 @@
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4
+};
+
+use meshio::{
+	physics::BodyMode,
+	Importer,
+	Language,
+	LocalizedStringMapExt,
+	WriteExt
+};
+
+use meshio_mmd::Pmx;
+
+/// Writes a UTF-8 PMX 2.0 model of one triangle skinned to a chain of bones
+///
+/// `parents` gives each bone's parent index, and each vertex carries `uva` additional UVs.
+fn model(uva: u8, parents: &[i32]) -> Vec<u8> {
+	let mut w = Vec::new();
+
+	w.extend_from_slice(b"PMX ");
+	w.write_le_f32(2.0).unwrap();
+	// Settings: UTF-8, additional UVs, then one-byte indices throughout
+	w.extend_from_slice(&[8, 1, uva, 1, 1, 1, 1, 1, 1]);
+	for name in ["モデル", "model", "", ""].iter() {
+		w.write_le_lstr(name).unwrap();
+	}
+
+	let positions = [Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)];
+	w.write_le_u32(3).unwrap();
+	for (i, &p) in positions.iter().enumerate() {
+		w.write_le_v3f(p).unwrap();
+		w.write_le_v3f(Vector3::new(0.0, 0.0, -1.0)).unwrap();
+		w.write_le_v2f(Vector2::new(p.x, p.y)).unwrap();
+		for set in 0..uva {
+			w.write_le_v4f(Vector4::new(i as f32, f32::from(set), 0.5, 0.25)).unwrap();
+		}
+		// BDEF1 on the last bone
+		w.write_u8(0).unwrap();
+		w.write_u8(parents.len() as u8 - 1).unwrap();
+		w.write_le_f32(1.0).unwrap();
+	}
+
+	w.write_le_u32(3).unwrap();
+	w.extend_from_slice(&[0, 1, 2]);
+	w.write_le_u32(0).unwrap();
+
+	// One material for the eyes, without an English name, using the first internal toon texture
+	w.write_le_u32(1).unwrap();
+	w.write_le_lstr("目").unwrap();
+	w.write_le_lstr("").unwrap();
+	for v in [1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 5.0, 0.5, 0.5, 0.5].iter() {
+		w.write_le_f32(*v).unwrap();
+	}
+	w.write_u8(0).unwrap();
+	for v in [0.0, 0.0, 0.0, 1.0, 1.0].iter() {
+		w.write_le_f32(*v).unwrap();
+	}
+	w.extend_from_slice(&[0xff, 0xff, 0, 1, 0]);
+	w.write_le_lstr("").unwrap();
+	w.write_le_u32(3).unwrap();
+
+	w.write_le_u32(parents.len() as u32).unwrap();
+	for (i, &parent) in parents.iter().enumerate() {
+		w.write_le_lstr(if i == 0 { "センター" } else { "頭" }).unwrap();
+		w.write_le_lstr("").unwrap();
+		w.write_le_v3f(Vector3::new(0.0, i as f32, 0.0)).unwrap();
+		w.write_u8(parent as u8).unwrap();
+		w.write_le_i32(0).unwrap();
+		// Rotatable, visible and enabled, with an offset tail
+		w.write_le_u16(2 | 8 | 16).unwrap();
+		w.write_le_v3f(Vector3::new(0.0, 1.0, 0.0)).unwrap();
+	}
+
+	// A vertex morph moving the second vertex, and a morph of the last additional UV moving
+	// the first
+	w.write_le_u32(1 + u32::from(uva > 0)).unwrap();
+	w.write_le_lstr("まばたき").unwrap();
+	w.write_le_lstr("").unwrap();
+	w.extend_from_slice(&[2, 1]);
+	w.write_le_i32(1).unwrap();
+	w.write_u8(1).unwrap();
+	w.write_le_v3f(Vector3::new(0.0, 0.5, 0.0)).unwrap();
+	if uva > 0 {
+		w.write_le_lstr("発光").unwrap();
+		w.write_le_lstr("glow").unwrap();
+		w.extend_from_slice(&[4, 3 + uva]);
+		w.write_le_i32(1).unwrap();
+		w.write_u8(0).unwrap();
+		w.write_le_v4f(Vector4::new(0.25, 0.5, 1.0, 1.0)).unwrap();
+	}
+
+	// No display frames
+	w.write_le_u32(0).unwrap();
+
+	// A body on the first bone in group 2 that ignores groups 0 and 2, and one on a missing bone
+	w.write_le_u32(2).unwrap();
+	for &(name, bone) in [("hair", 0), ("ghost", 9)].iter() {
+		w.write_le_lstr(name).unwrap();
+		w.write_le_lstr("").unwrap();
+		w.extend_from_slice(&[bone, 2]);
+		w.write_le_u16(0b101).unwrap();
+		w.write_u8(0).unwrap();
+		for v in [Vector3::new(0.5, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 0.0)].iter() {
+			w.write_le_v3f(*v).unwrap();
+		}
+		for v in [1.0, 0.5, 0.5, 0.0, 0.5].iter() {
+			w.write_le_f32(*v).unwrap();
+		}
+		w.write_u8(1).unwrap();
+	}
+
+	// No joints
+	w.write_le_u32(0).unwrap();
+	w
+}
+
+#[test]
+fn imports_model() {
+	let scene = Pmx.import(&model(0, &[-1, 0])).unwrap();
+	let object = &scene.objects[0];
+	let node = &object.nodes[0];
+	let skeleton = object.skeleton.as_ref().unwrap();
+
+	assert_eq!(scene.name.localized(&Language::English), Some("model"));
+	assert_eq!(node.vertices.len(), 3);
+	assert_eq!(node.faces.len(), 1);
+	assert_eq!(node.material, Some(0));
+	// Standard bone names do not apply to materials
+	assert_eq!(scene.materials[0].name.get(&Language::Japanese).map(String::as_str), Some("目"));
+	assert_eq!(scene.materials[0].name.get(&Language::English), None);
+
+	assert_eq!(skeleton.len(), 2);
+	assert_eq!(skeleton.find("center"), Some(0));
+	assert_eq!(skeleton.joints()[1].parent, Some(0));
+	assert_eq!(skeleton.joints()[1].translation, Vector3::new(0.0, 1.0, 0.0));
+
+	assert_eq!(object.morphs.len(), 1);
+	assert_eq!(object.morphs[0].name.localized(&Language::English), Some("blink"));
+	assert_eq!(object.morphs[0].offsets[0].position, Vector3::new(0.0, 0.5, 0.0));
+}
+
+#[test]
+fn rejects_truncated_model() {
+	let bytes = model(0, &[-1, 0]);
+	assert!(Pmx.import(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn bone_cycle_is_corrupt() {
+	match Pmx.import(&model(0, &[1, 0])) {
+		Err(meshio::Error::Corrupt { context, .. }) => assert_eq!(context, "bone table"),
+		other => panic!("expected a corrupt bone table, got {:?}", other.map(|s| s.objects.len())),
+	}
+}
+
+#[test]
+fn additional_uvs_keep_all_components() {
+	let scene = Pmx.import(&model(2, &[-1])).unwrap();
+	let node = &scene.objects[0].nodes[0];
+	let vertex = &node.vertices[2];
+
+	assert_eq!(node.uv_names, ["uv", "uva1.xy", "uva1.zw", "uva2.xy", "uva2.zw"]);
+	assert_eq!(vertex.uvs.len(), 5);
+	assert_eq!(vertex.uv(0), Vector2::new(0.0, 1.0));
+	assert_eq!(vertex.uv(node.uv_set("uva2.xy").unwrap()), Vector2::new(2.0, 1.0));
+	assert_eq!(vertex.uv(node.uv_set("uva2.zw").unwrap()), Vector2::new(0.5, 0.25));
+}
+
+#[test]
+fn additional_uv_morphs_target_their_set() {
+	let scene = Pmx.import(&model(2, &[-1])).unwrap();
+	let object = &scene.objects[0];
+	let set = object.nodes[0].uv_set("uva2.xy").unwrap();
+	let glow = object.find_morph("glow").unwrap();
+
+	assert_eq!(object.morphs[glow].uv_set, set);
+	assert_eq!(object.morphs[glow].offsets[0].uv, Vector2::new(0.25, 0.5));
+
+	let morphed = object.morphed(&[(glow, 2.0)]);
+	assert_eq!(morphed[0].vertices[0].uv(set), Vector2::new(0.5, 2.0));
+	assert_eq!(morphed[0].vertices[0].uv(0), Vector2::new(0.0, 0.0));
+}
+
+#[test]
+fn material_colors_are_linear() {
+	let scene = Pmx.import(&model(0, &[-1])).unwrap();
+	let material = &scene.materials[0];
+
+	// PMX colors are sRGB encoded, so an ambient of 0.5 is about 0.214 in linear light
+	assert!((material.ambient.red - 0.214).abs() < 1e-3);
+	assert_eq!(material.diffuse.red, 1.0);
+	assert_eq!(material.opacity, 1.0);
+}
+
+#[test]
+fn rigid_bodies_collide_with_the_groups_pmx_does_not_exclude() {
+	// The first bone is a child of the second, so the two swap places
+	let scene = Pmx.import(&model(0, &[1, -1])).unwrap();
+	let physics = &scene.objects[0].physics;
+	let hair = &physics.bodies[physics.find_body("hair").unwrap()];
+
+	assert_eq!(hair.joint, Some(1));
+	assert_eq!(hair.group, 1 << 2);
+	assert_eq!(hair.mask, 0xffff & !0b101);
+	assert_eq!(hair.mask & hair.group, 0);
+	assert_ne!(hair.mask & 1 << 1, 0);
+	assert_eq!(hair.transform.translation, Vector3::new(0.0, 1.0, 0.0));
+	assert_eq!(hair.mode, BodyMode::Dynamic);
+
+	assert_eq!(physics.bodies[physics.find_body("ghost").unwrap()].joint, None);
+}
@@ -0,0 +1,108 @@
+use cgmath::{
+	EuclideanSpace,
+	InnerSpace,
+	Matrix,
+	Matrix4,
+	Point3,
+	SquareMatrix,
+	Transform
+};
+
+use std::io::Write;
+
+use meshio::{
+	Exporter,
+	Format,
+	Language,
+	LocalizedStringMap,
+	LocalizedStringMapExt,
+	SceneF
+};
+
+/// Importer and exporter for Wavefront OBJ models
+///
+/// Reading gives one object per `o` statement and one node per group and material, with a
+/// vertex for each distinct combination of position, texture coordinate and normal. Materials
+/// are created by name from `usemtl` without reading MTL libraries, and statements without a
+/// core equivalent, such as lines and smoothing groups, are skipped.
+///
+/// Every placed object is written with its world transform applied, one group per node.
+/// Materials are referenced by name with `usemtl`, but no MTL library is written. OBJ has a single
+/// texture coordinate per vertex, so only the first UV set is kept.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct Obj;
+
+/// Returns an English name without whitespace, since OBJ statements are split on it
+fn name(map: &LocalizedStringMap, fallback: &str) -> String {
+	map.localized(&Language::English)
+		.unwrap_or(fallback)
+		.split_whitespace()
+		.collect::<Vec<_>>()
+		.join("_")
+}
+
+impl Exporter for Obj {
+	fn format(&self) -> Format {
+		Format::Obj
+	}
+
+	fn export(&self, scene: &SceneF) -> meshio::Result<Vec<u8>> {
+		let mut buf = Vec::new();
+		let world = scene.world_matrices().unwrap_or_else(|_| vec![Matrix4::identity(); scene.nodes.len()]);
+		let mut placements: Vec<(usize, Matrix4<f32>)> = scene.nodes.iter()
+			.zip(world)
+			.filter_map(|(n, m)| n.object.map(|o| (o, m)))
+			.filter(|&(o, _)| o < scene.objects.len())
+			.collect();
+
+		for o in 0..scene.objects.len() {
+			if scene.instances(o).next().is_none() {
+				placements.push((o, Matrix4::identity()));
+			}
+		}
+
+		let mut base = 1;
+
+		for (o, matrix) in placements {
+			let object = &scene.objects[o];
+			let normal_matrix = matrix.invert().unwrap_or_else(Matrix4::identity).transpose();
+
+			writeln!(buf, "o {}", name(&object.name, &format!("object{}", o)))?;
+
+			for (n, node) in object.nodes.iter().enumerate() {
+				writeln!(buf, "g {}", name(&node.name, &format!("node{}", n)))?;
+
+				if let Some(material) = node.material.and_then(|m| scene.materials.get(m)) {
+					writeln!(buf, "usemtl {}", name(&material.name, &format!("material{}", node.material.unwrap())))?;
+				}
+
+				for v in node.vertices.iter() {
+					let p = matrix.transform_point(Point3::from_vec(v.position));
+					writeln!(buf, "v {} {} {}", p.x, p.y, p.z)?;
+				}
+				for v in node.vertices.iter() {
+					let uv = v.uv(0);
+					writeln!(buf, "vt {} {}", uv.x, uv.y)?;
+				}
+				for v in node.vertices.iter() {
+					let n = normal_matrix.transform_vector(v.normals[0]);
+					let n = if n.magnitude2() > 0.0 { n.normalize() } else { n };
+					writeln!(buf, "vn {} {} {}", n.x, n.y, n.z)?;
+				}
+
+				for face in node.faces.iter() {
+					write!(buf, "f")?;
+					for i in face.indices() {
+						let i = base + i as usize;
+						write!(buf, " {}/{}/{}", i, i, i)?;
+					}
+					writeln!(buf)?;
+				}
+
+				base += node.vertices.len();
+			}
+		}
+
+		Ok(buf)
+	}
+}
@@ -17,7 +17,7 @@ use nom::{
 
 use meshio::uint;
 
-use super::{
+use super::obj::{
 	id,
 	vector3
 };
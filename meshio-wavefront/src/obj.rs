@@ -1,179 +1,296 @@
 use cgmath::{
+	Vector2,
 	Vector3,
-	Vector4
+	Vector4,
+	Zero
 };
 
 use nom::{
-	alt,
-	character::complete::float,
-	do_parse,
-	many0,
-	many1,
-	named,
-	opt,
-	tag,
-	tag_no_case,
-	ws
+	bytes::complete::{
+		tag,
+		take_till,
+		take_till1
+	},
+	character::complete::{
+		line_ending,
+		not_line_ending,
+		space0,
+		space1
+	},
+	combinator::{
+		map,
+		opt,
+		verify
+	},
+	multi::many1,
+	number::complete::float,
+	sequence::preceded,
+	IResult
 };
 
-use meshio::uint;
+use std::collections::HashMap;
 
-use super::{
-	id,
-	vector3
+use meshio::{
+	int,
+	Face,
+	Format,
+	Importer,
+	Language,
+	LocalizedStringMap,
+	Material,
+	Node,
+	Object,
+	Position,
+	SceneF,
+	Vertex
 };
 
-type Face = Vector3<[u32; 3]>;
+use crate::Obj;
 
-struct Group {
-	name: Option<String>,
-	material: Option<String>,
-	vertices: Vec<Vector4<f32>>,
-	normals: Vec<Vector3<f32>>,
-	uvs: Vec<Vector3<f32>>,
-	psvs: Vec<Vector3<f32>>,
-	faces: Vec<Face>,
-	lines: Vec<Vec<u32>>,
-	smoothing: u32,
-};
+/// One line of an OBJ file
+enum Statement<'a> {
+	Position(Vector3<f32>),
+	Uv(Vector2<f32>),
+	Normal(Vector3<f32>),
+	/// Position, texture coordinate and normal indices of each corner, with 0 for none
+	Face(Vec<[i32; 3]>),
+	Object(&'a [u8]),
+	Group(&'a [u8]),
+	UseMaterial(&'a [u8]),
+	/// A statement without a core equivalent, such as smoothing groups, lines or `mtllib`
+	Other,
+}
+
+/// Parses the space separated numbers that follow a keyword
+fn floats(input: &[u8]) -> IResult<&[u8], Vec<f32>> {
+	many1(preceded(space1, float))(input)
+}
+
+/// Parses the optional name that follows a keyword, up to a comment or the end of the line
+fn name(input: &[u8]) -> IResult<&[u8], &[u8]> {
+	let name = take_till(|c| c == b'#' || c == b'\r' || c == b'\n');
+	map(opt(preceded(space1, name)), |n: Option<&[u8]>| n.unwrap_or(b""))(input)
+}
+
+/// Parses one face corner as `v`, `v/vt`, `v//vn` or `v/vt/vn`
+fn corner(input: &[u8]) -> IResult<&[u8], [i32; 3]> {
+	let (input, position) = int(input)?;
+	let (input, uv) = opt(preceded(tag("/"), opt(int)))(input)?;
+	let (input, normal) = opt(preceded(tag("/"), int))(input)?;
+
+	Ok((input, [position, uv.and_then(|u| u).unwrap_or(0), normal.unwrap_or(0)]))
+}
+
+/// Parses a statement, leaving the line ending in place
+fn statement(input: &[u8]) -> IResult<&[u8], Statement<'_>> {
+	let (rest, keyword) = take_till1(|c: u8| c.is_ascii_whitespace())(input)?;
+	let (rest, statement) = match keyword {
+		b"v" => map(verify(floats, |f: &Vec<f32>| f.len() >= 3),
+			|f| Statement::Position(Vector3::new(f[0], f[1], f[2])))(rest)?,
+		b"vt" => map(floats, |f| Statement::Uv(Vector2::new(f[0], f.get(1).cloned().unwrap_or(0.0))))(rest)?,
+		b"vn" => map(verify(floats, |f: &Vec<f32>| f.len() >= 3),
+			|f| Statement::Normal(Vector3::new(f[0], f[1], f[2])))(rest)?,
+		b"f" => map(verify(many1(preceded(space1, corner)), |c: &Vec<[i32; 3]>| c.len() >= 3), Statement::Face)(rest)?,
+		b"o" => map(name, Statement::Object)(rest)?,
+		b"g" => map(name, Statement::Group)(rest)?,
+		b"usemtl" => map(name, Statement::UseMaterial)(rest)?,
+		_ => map(not_line_ending, |_| Statement::Other)(rest)?,
+	};
+	let (rest, _) = space0(rest)?;
+	let (rest, _) = opt(preceded(tag("#"), not_line_ending))(rest)?;
 
-struct Object {
-	name: String,
-	groups: Vec<Group>,
+	Ok((rest, statement))
 }
 
-impl Object {
-	fn new_with_anon_group() -> Object {
-		
+/// Converts an OBJ name to a name map, treating it as English
+fn localized(name: &[u8]) -> LocalizedStringMap {
+	let mut map = LocalizedStringMap::new();
+	let name = String::from_utf8_lossy(name).trim().to_owned();
+
+	if !name.is_empty() { map.insert(Language::English, name); }
+	map
+}
+
+/// Collects statements into a scene
+#[derive(Default)]
+struct Builder {
+	positions: Vec<Vector3<f32>>,
+	uvs: Vec<Vector2<f32>>,
+	normals: Vec<Vector3<f32>>,
+	scene: SceneF,
+	/// Index of each material by name, in order of first use
+	materials: HashMap<Vec<u8>, usize>,
+	/// Material applied to faces from here on
+	material: Option<usize>,
+	/// Vertex of the current node created for each position, texture coordinate and normal
+	corners: HashMap<[usize; 3], u32>,
+}
+
+impl Builder {
+	fn object(&mut self) -> &mut Object<f32, f32> {
+		if self.scene.objects.is_empty() {
+			self.scene.objects.push(Object::default());
+		}
+		self.scene.objects.last_mut().unwrap()
+	}
+
+	/// Starts a node that faces are added to from here on
+	fn start_node(&mut self, name: LocalizedStringMap) {
+		let material = self.material;
+
+		self.corners.clear();
+		self.object().nodes.push(Node {
+			name: name,
+			material: material,
+			..Default::default()
+		});
+	}
+
+	fn node(&mut self) -> &mut Node<f32, f32> {
+		if self.object().nodes.is_empty() {
+			self.start_node(LocalizedStringMap::new());
+		}
+		self.object().nodes.last_mut().unwrap()
+	}
+
+	fn use_material(&mut self, name: &[u8]) {
+		let count = self.scene.materials.len();
+		let index = *self.materials.entry(name.to_vec()).or_insert(count);
+
+		if index == count {
+			self.scene.materials.push(Material {
+				name: localized(name),
+				..Default::default()
+			});
+		}
+		self.material = Some(index);
+
+		// Faces already in the node keep their material, so the rest go in a node of their own
+		if self.node().faces.is_empty() {
+			self.node().material = Some(index);
+		} else {
+			let name = self.node().name.clone();
+			self.start_node(name);
+		}
+	}
+
+	/// Resolves a 1-based index, or a negative one counting back from the latest element
+	fn resolve(index: i32, len: usize) -> Option<usize> {
+		let i = if index < 0 { len as i64 + i64::from(index) } else { i64::from(index) - 1 };
+		if i >= 0 && (i as usize) < len { Some(i as usize) } else { None }
+	}
+
+	/// Adds a face, returning false if one of its corners indexes past the elements read so far
+	fn face(&mut self, corners: &[[i32; 3]]) -> bool {
+		let mut indices = Vec::with_capacity(corners.len());
+
+		for c in corners {
+			let position = match Builder::resolve(c[0], self.positions.len()) {
+				Some(p) => p,
+				None => return false,
+			};
+			let uv = match c[1] {
+				0 => None,
+				i => match Builder::resolve(i, self.uvs.len()) {
+					Some(i) => Some(i),
+					None => return false,
+				},
+			};
+			let normal = match c[2] {
+				0 => None,
+				i => match Builder::resolve(i, self.normals.len()) {
+					Some(i) => Some(i),
+					None => return false,
+				},
+			};
+
+			// Offset by one so that 0 can stand for a missing element
+			let key = [position + 1, uv.map_or(0, |i| i + 1), normal.map_or(0, |i| i + 1)];
+			let vertex = Vertex {
+				position: self.positions[position],
+				normals: [normal.map_or_else(Vector3::zero, |i| self.normals[i]), Vector3::zero()],
+				tangent: Vector4::zero(),
+				uvs: uv.map(|i| self.uvs[i]).into_iter().collect(),
+				colors: Vec::new(),
+				weights: Default::default(),
+			};
+			let count = self.node().vertices.len() as u32;
+			let index = *self.corners.entry(key).or_insert(count);
+
+			if index == count {
+				self.node().vertices.push(vertex);
+			}
+			indices.push(index);
+		}
+
+		self.node().faces.push(Face::from_indices(indices));
+		true
+	}
+
+	/// Drops nodes without faces and then objects without nodes
+	fn finish(mut self) -> SceneF {
+		for object in self.scene.objects.iter_mut() {
+			object.nodes.retain(|n| !n.faces.is_empty());
+		}
+		self.scene.objects.retain(|o| !o.nodes.is_empty());
+		self.scene.coordinate_system = Format::Obj.coordinate_system();
+		self.scene
 	}
 }
 
-named!(v<Vector4<f32>>,
-	ws!(do_parse!(
-		tag_no_case!('v') >>
-		x: float >>
-		y: float >>
-		z: float >>
-		w: opt!(float) >>
-		(Vector4::new(x, y, z, if w.is_none() { 1.0 } else { w.unwrap() }))
-	))
-);
-
-named!(vt<Vector3<f32>>,
-	ws!(do_parse!(
-		tag_no_case!("vt") >>
-		u: float >>
-		v: float >>
-		w: opt!(float) >>
-		(Vector3::new(x, y, if w.is_none() { 0.0 } else { w.unwrap() }))
-	))
-);
-
-named!(vn<Vector3<f32>>,
-	ws!(do_parse!(
-		tag_no_case!("vn") >>
-		v: vector3 >>
-		(v)
-	))
-);
-
-named!(vp<Vector3<f32>>,
-	ws!(do_parse!(
-		tag_no_case!("vp") >>
-		u: float >>
-		v: opt!(float) >>
-		w: opt!(float) >>
-		(Vector3::new(x,
-			if v.is_none() { 1.0 } else { v.unwrap() },
-			if w.is_none() { 0.0 } else { w.unwrap() }
-		))
-	))
-);
-
-named!(face_sub<[u32; 3]>,
-	ws!(do_parse!(
-		x: uint >>
-		y: opt!(do_parse!(
-			tag!('/') >>
-			f: opt!(uint) >>
-			(f)
-		)) >>
-		z: opt!(do_parse!(
-			tag!('/') >>
-			f: opt!(uint) >>
-			(f)
-		)) >>
-		([x,
-			if y.is_none() { 0 } else { y.unwrap() },
-			if z.is_none() { 0 } else { z.unwrap() }
-		])
-	))
-);
-
-named!(f<Face>,
-	ws!(do_parse!(
-		tag_no_case!('f') >>
-		x: face_sub >>
-		y: face_sub >>
-		z: face_sub >>
-		(Face {
-			x: x,
-			y: y,
-			z: z,
-		})
-	))
-);
-
-named!(l<Vec<u32> >,
-	ws!(do_parse!(
-		tag_no_case!('l') >>
-		data: many1!(uint) >>
-		(data)
-	))
-);
-
-named!(mtllib<&str>,
-	ws!(do_parse!(
-		tag_no_case!("mtllib") >>
-		name: id >>
-		(name)
-	))
-);
-
-named!(usemtl<&str>,
-	ws!(do_parse!(
-		tag_no_case!("usemtl") >>
-		name: id >>
-		(name)
-	))
-);
-
-named!(g<Group>,
-	ws!(do_parse!(
-		tag_no_case!('g') >>
-		name: id >>
-		data: many1!(alt!(v | vn | vp | vt | f | l | s | usemtl)) >>
-		(Group {
-			name: Some(name.to_owned()),
-		})
-	))
-);
-
-named!(o<Object>,
-	ws!(do_parse!(
-		tag_no_case!('o') >>
-		name: id >>
-		data: alt!(many1!(g) |
-			many1!(alt!(v | vn | vp | vt | f | l | s | g | usemtl))
-		) >>
-		(name, data)
-	))
-);
-
-named!(s,
-	ws!(do_parse!(
-		tag_no_case!('s') >>
-		val: alt!(tag_no_case!("off") | uint) >>
-		(val)
-	))
-);
+impl Importer for Obj {
+	fn format(&self) -> Format {
+		Format::Obj
+	}
+
+	fn import(&self, input: &[u8]) -> meshio::Result<SceneF> {
+		let error = |e| meshio::Error::parse_text(Format::Obj, "statement", input, e);
+		let mut builder = Builder::default();
+		let mut rest = input;
+
+		loop {
+			rest = space0(rest).map_err(error)?.0;
+
+			if rest.is_empty() {
+				break;
+			}
+			if rest[0] == b'#' || rest[0] == b'\r' || rest[0] == b'\n' {
+				rest = not_line_ending(rest).map_err(error)?.0;
+			} else {
+				let start = input.len() - rest.len();
+				let (r, statement) = statement(rest).map_err(error)?;
+				rest = r;
+
+				match statement {
+					Statement::Position(p) => builder.positions.push(p),
+					Statement::Uv(uv) => builder.uvs.push(uv),
+					Statement::Normal(n) => builder.normals.push(n),
+					Statement::Face(corners) => if !builder.face(&corners) {
+						return Err(meshio::Error::Corrupt {
+							format: Format::Obj,
+							context: "face".to_owned(),
+							position: Position::line_column(input, start),
+							cause: "index out of range".to_owned(),
+						});
+					},
+					Statement::Object(name) => {
+						builder.corners.clear();
+						builder.scene.objects.push(Object {
+							name: localized(name),
+							..Default::default()
+						});
+					},
+					Statement::Group(name) => builder.start_node(localized(name)),
+					Statement::UseMaterial(name) => builder.use_material(name),
+					Statement::Other => {},
+				}
+			}
+
+			if !rest.is_empty() {
+				rest = line_ending(rest).map_err(error)?.0;
+			}
+		}
+
+		Ok(builder.finish())
+	}
+}
@@ -0,0 +1,103 @@
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4
+};
+
+use meshio::{
+	Exporter,
+	Face,
+	Importer,
+	Language,
+	LocalizedStringMapExt,
+	Position
+};
+
+use meshio_wavefront::Obj;
+
+const CUBE_SIDE: &str = "\
+# Two faces of a cube
+mtllib cube.mtl
+o cube
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 1 0 -1
+v 1 1 -1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+vn 1 0 0
+g front
+usemtl red
+s 1
+f 1/1/1 2/2/1 3/3/1 4/4/1
+usemtl blue # the side
+f 2/1/2 5/2/2 -1/3/2 3/4/2
+g empty
+";
+
+#[test]
+fn imports_groups_and_materials() {
+	let scene = Obj.import(CUBE_SIDE.as_bytes()).unwrap();
+	let object = &scene.objects[0];
+
+	assert_eq!(scene.objects.len(), 1);
+	assert_eq!(object.name.localized(&Language::English), Some("cube"));
+	let materials: Vec<Option<&str>> = scene.materials.iter().map(|m| m.name.localized(&Language::English)).collect();
+	assert_eq!(materials, [Some("red"), Some("blue")]);
+
+	// Changing material within a group splits it, and the empty group is dropped
+	assert_eq!(object.nodes.len(), 2);
+	for (node, material) in object.nodes.iter().zip(0..) {
+		assert_eq!(node.name.localized(&Language::English), Some("front"));
+		assert_eq!(node.material, Some(material));
+		assert_eq!(node.vertices.len(), 4);
+	}
+
+	let side = &object.nodes[1];
+	assert_eq!(side.faces, [Face::Quad(Vector4::new(0, 1, 2, 3))]);
+	assert_eq!(side.vertices[2].position, Vector3::new(1.0, 1.0, -1.0));
+	assert_eq!(side.vertices[2].normals[0], Vector3::new(1.0, 0.0, 0.0));
+	assert_eq!(side.vertices[2].uvs, [Vector2::new(1.0, 1.0)]);
+}
+
+#[test]
+fn shares_vertices_with_the_same_indices() {
+	let input = "v 0 0 0\r\nv 1 0 0\r\nv 0 1 0\r\nv 1 1 0\r\nf 1 2 3\r\nf 3 2 4\r\nf 1//1 2 3\r\nvn 0 0 1";
+	// The normal is only defined after the face that uses it
+	assert!(Obj.import(input.as_bytes()).is_err());
+
+	let scene = Obj.import(input.replace("f 1//1 2 3\r\n", "").as_bytes()).unwrap();
+	let node = &scene.objects[0].nodes[0];
+	assert_eq!(node.vertices.len(), 4);
+	assert_eq!(node.faces[1], Face::Triangle(Vector3::new(2, 1, 3)));
+	assert!(node.vertices[0].uvs.is_empty());
+}
+
+#[test]
+fn reports_where_errors_are() {
+	match Obj.import(b"v 0 0 0\nv 1 0\n") {
+		Err(meshio::Error::Corrupt { position, .. }) => assert_eq!(position, Position::LineColumn { line: 2, column: 2 }),
+		other => panic!("expected corrupt data, got {:?}", other.map(|s| s.objects.len())),
+	}
+	match Obj.import(b"v 0 0 0\n\nf 1 1 2\n") {
+		Err(meshio::Error::Corrupt { context, position, .. }) => {
+			assert_eq!(context, "face");
+			assert_eq!(position, Position::LineColumn { line: 3, column: 1 });
+		},
+		other => panic!("expected an out of range face, got {:?}", other.map(|s| s.objects.len())),
+	}
+}
+
+#[test]
+fn exported_files_import_again() {
+	let scene = Obj.import(CUBE_SIDE.as_bytes()).unwrap();
+	let again = Obj.import(&Obj.export(&scene).unwrap()).unwrap();
+
+	assert_eq!(again.objects[0].nodes, scene.objects[0].nodes);
+	assert_eq!(again.materials, scene.materials);
+}
@@ -0,0 +1,311 @@
+//! Keyframe animation of skeleton joints
+
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Matrix4,
+	Quaternion,
+	Vector3,
+	Vector4
+};
+
+use std::cmp::Ordering;
+
+use crate::{
+	LocalizedStringMap,
+	Skeleton
+};
+
+/// Values with components of type `T` that can be keyed and interpolated over time
+pub trait Animatable<T: BaseFloat>: Copy {
+	/// Adds two values component-wise
+	fn add(self, other: Self) -> Self;
+
+	/// Multiplies every component by `s`
+	fn scale(self, s: T) -> Self;
+
+	/// Interpolates linearly towards `other`
+	fn lerp(self, other: Self, t: T) -> Self {
+		self.scale(T::one() - t).add(other.scale(t))
+	}
+
+	/// Brings a blended value back into its valid range, such as unit length for rotations
+	fn renormalize(self) -> Self {
+		self
+	}
+
+	/// Returns true if `other` must be negated to blend along the shortest path, as for
+	/// rotations in opposite hemispheres
+	fn opposes(self, _other: Self) -> bool {
+		false
+	}
+}
+
+impl Animatable<f32> for f32 {
+	fn add(self, other: f32) -> f32 { self + other }
+	fn scale(self, s: f32) -> f32 { self * s }
+}
+
+impl Animatable<f64> for f64 {
+	fn add(self, other: f64) -> f64 { self + other }
+	fn scale(self, s: f64) -> f64 { self * s }
+}
+
+impl<T: BaseFloat> Animatable<T> for Vector3<T> {
+	fn add(self, other: Vector3<T>) -> Vector3<T> { self + other }
+	fn scale(self, s: T) -> Vector3<T> { self * s }
+}
+
+impl<T: BaseFloat> Animatable<T> for Vector4<T> {
+	fn add(self, other: Vector4<T>) -> Vector4<T> { self + other }
+	fn scale(self, s: T) -> Vector4<T> { self * s }
+}
+
+impl<T: BaseFloat> Animatable<T> for Quaternion<T> {
+	fn add(self, other: Quaternion<T>) -> Quaternion<T> { self + other }
+	fn scale(self, s: T) -> Quaternion<T> { self * s }
+
+	/// Spherical interpolation along the shortest arc
+	fn lerp(self, other: Quaternion<T>, t: T) -> Quaternion<T> {
+		let other = if self.opposes(other) { -other } else { other };
+		self.slerp(other, t)
+	}
+
+	fn renormalize(self) -> Quaternion<T> {
+		self.normalize()
+	}
+
+	fn opposes(self, other: Quaternion<T>) -> bool {
+		self.dot(other) < T::zero()
+	}
+}
+
+/// How values between two keyframes are computed
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum Interpolation {
+	/// Holds the value of the previous key
+	Constant,
+	Linear,
+	/// Cubic Hermite spline, with tangents given as rates of change per unit of time
+	Hermite,
+	/// Cubic Bezier curve, with tangents given as the offsets of the control points from their
+	/// key's value
+	Bezier,
+}
+
+/// A value at a point in time, with tangents used by cubic interpolation
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Keyframe<T, V> {
+	pub time: T,
+	pub value: V,
+	/// Tangent arriving at this key from the previous one
+	pub in_tangent: V,
+	/// Tangent leaving this key towards the next one
+	pub out_tangent: V,
+}
+
+impl<T: BaseFloat, V: Animatable<T>> Keyframe<T, V> {
+	/// Creates a key with flat tangents, which hold the value still around the key in both
+	/// cubic modes
+	pub fn new(time: T, value: V) -> Keyframe<T, V> {
+		Keyframe {
+			time: time,
+			value: value,
+			in_tangent: value.scale(T::zero()),
+			out_tangent: value.scale(T::zero()),
+		}
+	}
+}
+
+/// A sequence of keyframes sharing one interpolation mode, ordered by time
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Track<T, V> {
+	pub interpolation: Interpolation,
+	pub keys: Vec<Keyframe<T, V>>,
+}
+
+impl<T, V> Default for Track<T, V> {
+	fn default() -> Track<T, V> {
+		Track {
+			interpolation: Interpolation::Linear,
+			keys: Vec::new(),
+		}
+	}
+}
+
+impl<T: BaseFloat, V: Animatable<T>> Track<T, V> {
+	/// Creates a track, sorting the keys by time
+	pub fn new(interpolation: Interpolation, mut keys: Vec<Keyframe<T, V>>) -> Track<T, V> {
+		keys.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal));
+		Track {
+			interpolation: interpolation,
+			keys: keys,
+		}
+	}
+
+	/// Returns true if the track has no keys
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	/// Returns the time of the last key
+	pub fn end(&self) -> Option<T> {
+		self.keys.last().map(|k| k.time)
+	}
+
+	/// Evaluates the track at `time`, holding the first and last values outside the keyed range
+	pub fn sample(&self, time: T) -> Option<V> {
+		let first = self.keys.first()?;
+		let last = self.keys.last()?;
+
+		if time <= first.time {
+			return Some(first.value);
+		}
+		if time >= last.time {
+			return Some(last.value);
+		}
+
+		let next = self.keys.iter().position(|k| k.time > time)?;
+		let (a, b) = (&self.keys[next - 1], &self.keys[next]);
+		let dt = b.time - a.time;
+		let t = (time - a.time) / dt;
+		let one = T::one();
+		let two = one + one;
+		let three = two + one;
+
+		// Cubic modes blend components directly, so the far key is flipped onto the near one's
+		// side first, tangents included
+		let (b_value, b_in) = if a.value.opposes(b.value) {
+			(b.value.scale(-one), b.in_tangent.scale(-one))
+		} else {
+			(b.value, b.in_tangent)
+		};
+
+		let value = match self.interpolation {
+			Interpolation::Constant => a.value,
+			Interpolation::Linear => a.value.lerp(b.value, t),
+			Interpolation::Hermite => {
+				let t2 = t * t;
+				let t3 = t2 * t;
+				let h00 = two * t3 - three * t2 + one;
+				let h10 = t3 - two * t2 + t;
+				let h01 = three * t2 - two * t3;
+				let h11 = t3 - t2;
+
+				a.value.scale(h00)
+					.add(a.out_tangent.scale(h10 * dt))
+					.add(b_value.scale(h01))
+					.add(b_in.scale(h11 * dt))
+					.renormalize()
+			},
+			Interpolation::Bezier => {
+				let u = one - t;
+
+				a.value.scale(u * u * u)
+					.add(a.value.add(a.out_tangent).scale(three * u * u * t))
+					.add(b_value.add(b_in).scale(three * u * t * t))
+					.add(b_value.scale(t * t * t))
+					.renormalize()
+			},
+		};
+
+		Some(value)
+	}
+}
+
+/// Translation, rotation and scale tracks that animate one joint
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Channel<T> {
+	/// Index of the animated joint in the object's [`Skeleton`]
+	pub joint: usize,
+	pub translation: Track<T, Vector3<T>>,
+	pub rotation: Track<T, Quaternion<T>>,
+	pub scale: Track<T, Vector3<T>>,
+}
+
+impl<T: BaseFloat> Channel<T> {
+	/// Creates a channel with no keys
+	pub fn new(joint: usize) -> Channel<T> {
+		Channel {
+			joint: joint,
+			translation: Track::default(),
+			rotation: Track::default(),
+			scale: Track::default(),
+		}
+	}
+}
+
+/// A named animation, such as a walk cycle, made of joint channels
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Clip<T> {
+	pub name: LocalizedStringMap,
+	/// Length of the clip in seconds
+	pub duration: T,
+	/// Whether sampling past the end wraps back to the start
+	pub looping: bool,
+	pub channels: Vec<Channel<T>>,
+}
+
+impl<T: BaseFloat> Clip<T> {
+	/// Creates an empty clip
+	pub fn new(name: LocalizedStringMap, duration: T) -> Clip<T> {
+		Clip {
+			name: name,
+			duration: duration,
+			looping: false,
+			channels: Vec::new(),
+		}
+	}
+
+	/// Returns the channel animating `joint`
+	pub fn channel(&self, joint: usize) -> Option<&Channel<T>> {
+		self.channels.iter().find(|c| c.joint == joint)
+	}
+
+	/// Maps a time to the clip's range, wrapping for looping clips
+	pub fn local_time(&self, time: T) -> T {
+		if self.looping && self.duration > T::zero() {
+			let t = time % self.duration;
+			if t < T::zero() { t + self.duration } else { t }
+		} else {
+			time
+		}
+	}
+
+	/// Computes the parent-relative transform of every joint of `skeleton` at `time`
+	///
+	/// Joints or components without keys keep their rest pose.
+	pub fn sample(&self, skeleton: &Skeleton<T>, time: T) -> Vec<Matrix4<T>> {
+		let time = self.local_time(time);
+
+		skeleton.joints().iter().enumerate().map(|(i, joint)| {
+			let mut pose = joint.rest();
+
+			if let Some(channel) = self.channel(i) {
+				pose.translation = channel.translation.sample(time).unwrap_or(pose.translation);
+				pose.rotation = channel.rotation.sample(time).unwrap_or(pose.rotation);
+				pose.scale = channel.scale.sample(time).unwrap_or(pose.scale);
+			}
+
+			pose.matrix()
+		}).collect()
+	}
+
+	/// Computes the model space transform of every joint of `skeleton` at `time`
+	pub fn sample_world(&self, skeleton: &Skeleton<T>, time: T) -> Vec<Matrix4<T>> {
+		let mut pose = self.sample(skeleton, time);
+
+		for i in 0..pose.len() {
+			if let Some(parent) = skeleton.joints()[i].parent {
+				pose[i] = pose[parent] * pose[i];
+			}
+		}
+
+		pose
+	}
+}
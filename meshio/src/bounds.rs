@@ -1,4 +1,12 @@
-use cgmath::Vector3;
+use cgmath::{
+	BaseFloat,
+	EuclideanSpace,
+	InnerSpace,
+	Matrix4,
+	Point3,
+	Transform,
+	Vector3
+};
 
 use nom::{
 	do_parse,
@@ -11,8 +19,19 @@ use nom::{
 	}
 };
 
+use crate::{
+	be_v3d,
+	be_v3f,
+	le_v3d,
+	le_v3f,
+	Node,
+	Object,
+	Ray
+};
+
 /// Bounds with minimum and maximum point limits and radius in 3D space
-#[derive(Clone,Debug,Default,PartialEq)]
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Bounds<T> {
 	pub min: Vector3<T>,
 	pub max: Vector3<T>,
@@ -25,6 +44,144 @@ pub type Bounds32 = Bounds<f32>;
 /// Type alias for double-based [`Bounds`]
 pub type Bounds64 = Bounds<f64>;
 
+/// Type alias for single precision [`Bounds`], matching [`ColorF`](crate::ColorF)
+pub type BoundsF = Bounds<f32>;
+
+impl<T: BaseFloat> Bounds<T> {
+	/// Computes the box enclosing `points` and the radius of the sphere around its center that
+	/// encloses them, or `None` if there are no points
+	pub fn from_points<I: IntoIterator<Item = Vector3<T>>>(points: I) -> Option<Bounds<T>> {
+		let points: Vec<Vector3<T>> = points.into_iter().collect();
+		let first = *points.first()?;
+		let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+			(
+				Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+				Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+			)
+		});
+		let center = (min + max) / (T::one() + T::one());
+		let radius = points.iter().fold(T::zero(), |r, p| r.max((p - center).magnitude()));
+
+		Some(Bounds {
+			min: min,
+			max: max,
+			radius: Some(radius),
+		})
+	}
+
+	/// Returns the center of the box
+	pub fn center(&self) -> Vector3<T> {
+		(self.min + self.max) / (T::one() + T::one())
+	}
+
+	/// Returns the size of the box along each axis
+	pub fn size(&self) -> Vector3<T> {
+		self.max - self.min
+	}
+
+	/// Returns the radius, or the distance from the center to a corner if there is none
+	pub fn radius_or_corner(&self) -> T {
+		self.radius.unwrap_or_else(|| (self.max - self.center()).magnitude())
+	}
+
+	/// Returns bounds enclosing both `self` and `other`
+	///
+	/// The radius is kept only if both have one, grown so that both spheres fit around the new
+	/// center.
+	pub fn union(&self, other: &Bounds<T>) -> Bounds<T> {
+		let min = Vector3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z));
+		let max = Vector3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z));
+		let center = (min + max) / (T::one() + T::one());
+		let radius = match (self.radius, other.radius) {
+			(Some(a), Some(b)) => Some(((self.center() - center).magnitude() + a).max((other.center() - center).magnitude() + b)),
+			_ => None,
+		};
+
+		Bounds {
+			min: min,
+			max: max,
+			radius: radius,
+		}
+	}
+
+	/// Returns bounds enclosing the box and sphere after transforming them by `matrix`
+	///
+	/// The box stays axis aligned, so it grows under rotation. The radius is scaled by the
+	/// largest axis scale of `matrix`.
+	pub fn transform(&self, matrix: &Matrix4<T>) -> Bounds<T> {
+		let corners = (0..8).map(|i| {
+			let pick = |bit: usize, min: T, max: T| if i & bit == 0 { min } else { max };
+			let corner = Point3::new(pick(1, self.min.x, self.max.x), pick(2, self.min.y, self.max.y), pick(4, self.min.z, self.max.z));
+			matrix.transform_point(corner).to_vec()
+		});
+		let mut bounds = Bounds::from_points(corners).unwrap();
+		let moved = matrix.transform_point(Point3::from_vec(self.center())).to_vec();
+		let scale = matrix.x.truncate().magnitude()
+			.max(matrix.y.truncate().magnitude())
+			.max(matrix.z.truncate().magnitude());
+
+		bounds.radius = self.radius.map(|r| r * scale + (moved - bounds.center()).magnitude());
+		bounds
+	}
+
+	/// Returns true if `point` is inside or on the box
+	pub fn contains(&self, point: Vector3<T>) -> bool {
+		point.x >= self.min.x && point.y >= self.min.y && point.z >= self.min.z
+			&& point.x <= self.max.x && point.y <= self.max.y && point.z <= self.max.z
+	}
+
+	/// Returns true if the box of `other` is entirely inside the box
+	pub fn contains_bounds(&self, other: &Bounds<T>) -> bool {
+		self.contains(other.min) && self.contains(other.max)
+	}
+
+	/// Returns true if the boxes overlap or touch
+	pub fn intersects(&self, other: &Bounds<T>) -> bool {
+		self.min.x <= other.max.x && self.min.y <= other.max.y && self.min.z <= other.max.z
+			&& other.min.x <= self.max.x && other.min.y <= self.max.y && other.min.z <= self.max.z
+	}
+
+	/// Returns the distance along `ray` at which it enters the box, which is zero if the origin
+	/// is inside, or `None` if it misses
+	pub fn intersect_ray(&self, ray: &Ray<T>) -> Option<T> {
+		let mut near = T::zero();
+		let mut far = T::infinity();
+
+		for axis in 0..3 {
+			let (origin, direction) = (ray.origin[axis], ray.direction[axis]);
+
+			if direction == T::zero() {
+				if origin < self.min[axis] || origin > self.max[axis] {
+					return None;
+				}
+				continue;
+			}
+
+			let a = (self.min[axis] - origin) / direction;
+			let b = (self.max[axis] - origin) / direction;
+			near = near.max(a.min(b));
+			far = far.min(a.max(b));
+		}
+
+		if near <= far { Some(near) } else { None }
+	}
+}
+
+impl<P: BaseFloat, C> Node<P, C> {
+	/// Computes the bounds of the node's vertices, or `None` if it has none
+	pub fn bounds(&self) -> Option<Bounds<P>> {
+		Bounds::from_points(self.vertices.iter().map(|v| v.position))
+	}
+}
+
+impl<P: BaseFloat, C> Object<P, C> {
+	/// Computes the bounds of the vertices of all nodes in their bind pose, or `None` if there
+	/// are none
+	pub fn bounds(&self) -> Option<Bounds<P>> {
+		Bounds::from_points(self.nodes.iter().flat_map(|n| n.vertices.iter()).map(|v| v.position))
+	}
+}
+
 /// Parses a [`Bounds32`] in little endian with radius
 named!(pub le_bounds32_r<Bounds32>,
 	do_parse!(
@@ -0,0 +1,245 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Vector3
+};
+
+use crate::{
+	Bounds,
+	Node,
+	Object
+};
+
+/// A half-line from `origin` along `direction`
+///
+/// Distances along the ray are measured in multiples of `direction`, which need not be
+/// normalized.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Ray<T> {
+	pub origin: Vector3<T>,
+	pub direction: Vector3<T>,
+}
+
+impl<T: BaseFloat> Ray<T> {
+	pub fn new(origin: Vector3<T>, direction: Vector3<T>) -> Ray<T> {
+		Ray {
+			origin: origin,
+			direction: direction,
+		}
+	}
+
+	/// Returns the point at `distance` along the ray
+	pub fn at(&self, distance: T) -> Vector3<T> {
+		self.origin + self.direction * distance
+	}
+}
+
+/// A triangle of a [`Bvh`] and the face it was split from
+#[derive(Clone,Debug,PartialEq)]
+pub struct BvhTriangle<T> {
+	/// Index of the node in the object
+	pub node: usize,
+	/// Index of the face in the node
+	pub face: usize,
+	/// Vertex indices in the node
+	pub indices: Vector3<u32>,
+	pub corners: [Vector3<T>; 3],
+}
+
+/// The closest intersection of a ray with a [`Bvh`]
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Hit<T> {
+	/// Distance along the ray, in multiples of its direction
+	pub distance: T,
+	/// Index into [`Bvh::triangles`]
+	pub triangle: usize,
+	/// Weights of the triangle's three corners at the hit point
+	pub barycentric: Vector3<T>,
+}
+
+#[derive(Clone,Debug,PartialEq)]
+struct BvhNode<T> {
+	bounds: Bounds<T>,
+	/// First triangle of a leaf, or the right child of a branch whose left child follows it
+	first: usize,
+	/// Triangle count of a leaf, or zero for a branch
+	count: usize,
+}
+
+/// A bounding volume hierarchy over the triangles of an [`Object`], for picking and other ray
+/// queries
+///
+/// The hierarchy is built from the bind pose and is not updated when the object changes.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Bvh<T> {
+	triangles: Vec<BvhTriangle<T>>,
+	nodes: Vec<BvhNode<T>>,
+}
+
+/// Most triangles kept in a leaf
+const LEAF_SIZE: usize = 4;
+
+impl<T: BaseFloat> Bvh<T> {
+	/// Builds a hierarchy over every face of `object`, skipping faces that cannot be triangulated
+	pub fn from_object<C>(object: &Object<T, C>) -> Bvh<T> {
+		let mut triangles = Vec::new();
+
+		for (n, node) in object.nodes.iter().enumerate() {
+			triangles.extend(Bvh::node_triangles(n, node));
+		}
+		Bvh::build(triangles)
+	}
+
+	/// Builds a hierarchy over every face of a single node, reported as node 0
+	pub fn from_node<C>(node: &Node<T, C>) -> Bvh<T> {
+		Bvh::build(Bvh::node_triangles(0, node))
+	}
+
+	fn node_triangles<C>(n: usize, node: &Node<T, C>) -> Vec<BvhTriangle<T>> {
+		let positions: Vec<Vector3<T>> = node.vertices.iter().map(|v| v.position).collect();
+
+		node.faces.iter()
+			.enumerate()
+			.flat_map(|(f, face)| {
+				face.triangulate(&positions).unwrap_or_default()
+					.into_iter()
+					.map(|t| BvhTriangle {
+						node: n,
+						face: f,
+						indices: t,
+						corners: [positions[t.x as usize], positions[t.y as usize], positions[t.z as usize]],
+					})
+					.collect::<Vec<_>>()
+			})
+			.collect()
+	}
+
+	fn build(mut triangles: Vec<BvhTriangle<T>>) -> Bvh<T> {
+		let mut nodes = Vec::new();
+
+		if !triangles.is_empty() {
+			let count = triangles.len();
+			split(&mut triangles, 0, count, &mut nodes);
+		}
+		Bvh {
+			triangles: triangles,
+			nodes: nodes,
+		}
+	}
+
+	/// Returns the triangles in hierarchy order
+	pub fn triangles(&self) -> &[BvhTriangle<T>] {
+		&self.triangles
+	}
+
+	/// Returns the bounds of all triangles, or `None` if there are none
+	pub fn bounds(&self) -> Option<&Bounds<T>> {
+		self.nodes.first().map(|n| &n.bounds)
+	}
+
+	/// Returns the closest triangle hit by `ray` from either side
+	pub fn intersect(&self, ray: &Ray<T>) -> Option<Hit<T>> {
+		let mut closest: Option<Hit<T>> = None;
+		let mut stack = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+
+		while let Some(i) = stack.pop() {
+			let node = &self.nodes[i];
+
+			match node.bounds.intersect_ray(ray) {
+				Some(d) if closest.map_or(true, |c| d <= c.distance) => {},
+				_ => continue,
+			}
+
+			if node.count == 0 {
+				stack.push(node.first);
+				stack.push(i + 1);
+				continue;
+			}
+
+			for t in node.first..node.first + node.count {
+				if let Some((distance, barycentric)) = intersect_triangle(ray, &self.triangles[t].corners) {
+					if closest.map_or(true, |c| distance < c.distance) {
+						closest = Some(Hit {
+							distance: distance,
+							triangle: t,
+							barycentric: barycentric,
+						});
+					}
+				}
+			}
+		}
+
+		closest
+	}
+}
+
+/// Appends the node for `triangles[first..first + count]` and its descendants, splitting at the
+/// median centroid along the longest axis
+fn split<T: BaseFloat>(triangles: &mut [BvhTriangle<T>], first: usize, count: usize, nodes: &mut Vec<BvhNode<T>>) {
+	let range = &mut triangles[first..first + count];
+	let mut bounds = Bounds::from_points(range.iter().flat_map(|t| t.corners.iter().cloned())).unwrap();
+	bounds.radius = None;
+	let index = nodes.len();
+
+	nodes.push(BvhNode {
+		bounds: bounds,
+		first: first,
+		count: count,
+	});
+
+	if count <= LEAF_SIZE {
+		return;
+	}
+
+	let three = T::one() + T::one() + T::one();
+	let centroid = |t: &BvhTriangle<T>| (t.corners[0] + t.corners[1] + t.corners[2]) / three;
+	let spread = Bounds::from_points(range.iter().map(centroid)).unwrap().size();
+	let axis = if spread.x >= spread.y && spread.x >= spread.z {
+		0
+	} else if spread.y >= spread.z {
+		1
+	} else {
+		2
+	};
+	let half = count / 2;
+
+	range.select_nth_unstable_by(half, |a, b| {
+		centroid(a)[axis].partial_cmp(&centroid(b)[axis]).unwrap_or(std::cmp::Ordering::Equal)
+	});
+
+	nodes[index].count = 0;
+	split(triangles, first, half, nodes);
+	nodes[index].first = nodes.len();
+	split(triangles, first + half, count - half, nodes);
+}
+
+/// Intersects a ray with a triangle from either side, returning the distance and barycentric
+/// weights, following Möller and Trumbore
+fn intersect_triangle<T: BaseFloat>(ray: &Ray<T>, corners: &[Vector3<T>; 3]) -> Option<(T, Vector3<T>)> {
+	let (e1, e2) = (corners[1] - corners[0], corners[2] - corners[0]);
+	let p = ray.direction.cross(e2);
+	let det = e1.dot(p);
+
+	if det == T::zero() {
+		return None;
+	}
+
+	let s = ray.origin - corners[0];
+	let u = s.dot(p) / det;
+	if u < T::zero() || u > T::one() {
+		return None;
+	}
+
+	let q = s.cross(e1);
+	let v = ray.direction.dot(q) / det;
+	if v < T::zero() || u + v > T::one() {
+		return None;
+	}
+
+	let distance = e2.dot(q) / det;
+	if distance < T::zero() {
+		return None;
+	}
+
+	Some((distance, Vector3::new(T::one() - u - v, u, v)))
+}
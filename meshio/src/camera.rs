@@ -0,0 +1,63 @@
+use cgmath::{
+	perspective,
+	BaseFloat,
+	EuclideanSpace,
+	Matrix4,
+	Point3,
+	Rad,
+	SquareMatrix,
+	Transform as _,
+	Vector3
+};
+
+use crate::LocalizedStringMap;
+
+/// A perspective camera placed in a [`Scene`](crate::Scene) by the nodes that reference it by
+/// index
+///
+/// None of the supported formats store cameras yet, so only scenes built in code have them.
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Camera<T> {
+	pub name: LocalizedStringMap,
+	/// Vertical field of view in radians
+	pub fov: T,
+	/// Distance to the near clipping plane
+	pub near: T,
+	/// Distance to the far clipping plane
+	pub far: T,
+	/// Point in world space the camera looks at, or `None` to look along the node's -Z axis
+	pub target: Option<Vector3<T>>,
+}
+
+impl<T: BaseFloat> Camera<T> {
+	/// Creates an untargeted camera with the given field of view
+	pub fn new(name: LocalizedStringMap, fov: T) -> Camera<T> {
+		Camera {
+			name: name,
+			fov: fov,
+			near: T::from(0.1).unwrap(),
+			far: T::from(1000.0).unwrap(),
+			target: None,
+		}
+	}
+
+	/// Returns the right-handed projection matrix for a viewport of `aspect` width over height
+	pub fn projection(&self, aspect: T) -> Matrix4<T> {
+		perspective(Rad(self.fov), aspect, self.near, self.far)
+	}
+
+	/// Returns the view matrix of the camera placed by a node with world transform `world`
+	///
+	/// Targeted cameras keep the world Y axis up.
+	pub fn view(&self, world: Matrix4<T>) -> Matrix4<T> {
+		match self.target {
+			Some(target) => {
+				let eye = world.transform_point(Point3::origin());
+				let up = Vector3::new(T::zero(), T::one(), T::zero());
+				Matrix4::look_at(eye, Point3::from_vec(target), up)
+			},
+			None => world.invert().unwrap_or_else(Matrix4::identity),
+		}
+	}
+}
@@ -0,0 +1,249 @@
+//! Conversions between floats and the quantized and packed encodings formats store vertices in
+//!
+//! Every `encode_*` function rounds to the nearest representable value, so decoding a stored value
+//! and encoding it again gives back the original bits, apart from the aliases noted on each function.
+//!
+//! The M3 and ism2 functions follow those formats' published vertex layouts. The Blizzard and
+//! compileheart crates only parse the raw structures so far and do not call them yet.
+
+use cgmath::{
+	InnerSpace,
+	Vector2,
+	Vector3
+};
+
+use crate::{
+	Influence,
+	SkinWeights
+};
+
+/// Converts the bits of an IEEE 754 half precision float
+pub fn decode_half(h: u16) -> f32 {
+	let sign = u32::from(h >> 15) << 31;
+	let exponent = u32::from((h >> 10) & 0x1f);
+	let mantissa = u32::from(h & 0x3ff);
+
+	let bits = match exponent {
+		0 if mantissa == 0 => sign,
+		0 => {
+			// Subnormal, so shift the leading one into the implicit bit
+			let shift = mantissa.leading_zeros() - 21;
+			sign | ((113 - shift) << 23) | (((mantissa << shift) & 0x3ff) << 13)
+		},
+		0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+		_ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+	};
+
+	f32::from_bits(bits)
+}
+
+/// Converts to the bits of an IEEE 754 half precision float, rounding to nearest even
+///
+/// Values too large for a half become infinity.
+pub fn encode_half(v: f32) -> u16 {
+	let bits = v.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let exponent = ((bits >> 23) & 0xff) as i32;
+	let mantissa = bits & 0x7f_ffff;
+
+	if exponent == 0xff {
+		// Keep the top of a NaN payload, making sure it stays a NaN
+		let payload = (mantissa >> 13) as u16;
+		return sign | 0x7c00 | if mantissa != 0 && payload == 0 { 0x200 } else { payload };
+	}
+
+	let exponent = exponent - 127 + 15;
+
+	if exponent >= 0x1f {
+		return sign | 0x7c00;
+	}
+
+	let (half, remainder, halfway) = if exponent <= 0 {
+		if exponent < -10 {
+			return sign;
+		}
+		let shift = (14 - exponent) as u32;
+		let mantissa = mantissa | 0x80_0000;
+		(mantissa >> shift, mantissa & ((1 << shift) - 1), 1 << (shift - 1))
+	} else {
+		(((exponent as u32) << 10) | (mantissa >> 13), mantissa & 0x1fff, 0x1000)
+	};
+
+	// A carry out of the mantissa correctly bumps the exponent, up to infinity
+	let round = remainder > halfway || (remainder == halfway && half & 1 == 1);
+	sign | (half + round as u32) as u16
+}
+
+/// Maps a byte onto `[0, 1]`
+pub fn decode_unorm8(v: u8) -> f32 {
+	f32::from(v) / 255.0
+}
+
+/// Maps `[0, 1]` onto a byte, clamping values outside the range
+pub fn encode_unorm8(v: f32) -> u8 {
+	(v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Maps a signed byte onto `[-1, 1]`, with both -128 and -127 becoming -1
+pub fn decode_snorm8(v: i8) -> f32 {
+	(f32::from(v) / 127.0).max(-1.0)
+}
+
+/// Maps `[-1, 1]` onto a signed byte, clamping values outside the range
+pub fn encode_snorm8(v: f32) -> i8 {
+	(v.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
+
+/// Maps an unsigned 16-bit integer onto `[0, 1]`
+pub fn decode_unorm16(v: u16) -> f32 {
+	f32::from(v) / 65535.0
+}
+
+/// Maps `[0, 1]` onto an unsigned 16-bit integer, clamping values outside the range
+pub fn encode_unorm16(v: f32) -> u16 {
+	(v.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+/// Maps a signed 16-bit integer onto `[-1, 1]`, with both -32768 and -32767 becoming -1
+pub fn decode_snorm16(v: i16) -> f32 {
+	(f32::from(v) / 32767.0).max(-1.0)
+}
+
+/// Maps `[-1, 1]` onto a signed 16-bit integer, clamping values outside the range
+pub fn encode_snorm16(v: f32) -> i16 {
+	(v.clamp(-1.0, 1.0) * 32767.0).round() as i16
+}
+
+/// Returns one for positive values and zero, and minus one otherwise
+fn sign_not_zero(v: f32) -> f32 {
+	if v >= 0.0 { 1.0 } else { -1.0 }
+}
+
+/// Projects a unit vector onto the octahedron and unfolds it into the `[-1, 1]` square
+///
+/// A zero vector encodes as the origin, which decodes to positive Z.
+pub fn encode_octahedral(n: Vector3<f32>) -> Vector2<f32> {
+	let l1 = n.x.abs() + n.y.abs() + n.z.abs();
+
+	if l1 == 0.0 {
+		return Vector2::new(0.0, 0.0);
+	}
+
+	let p = Vector2::new(n.x / l1, n.y / l1);
+
+	if n.z < 0.0 {
+		Vector2::new((1.0 - p.y.abs()) * sign_not_zero(p.x), (1.0 - p.x.abs()) * sign_not_zero(p.y))
+	} else {
+		p
+	}
+}
+
+/// Folds a point of the `[-1, 1]` square back onto the octahedron, returning a unit vector
+pub fn decode_octahedral(e: Vector2<f32>) -> Vector3<f32> {
+	let z = 1.0 - e.x.abs() - e.y.abs();
+	let (x, y) = if z < 0.0 {
+		((1.0 - e.y.abs()) * sign_not_zero(e.x), (1.0 - e.x.abs()) * sign_not_zero(e.y))
+	} else {
+		(e.x, e.y)
+	};
+
+	Vector3::new(x, y, z).normalize()
+}
+
+/// Encodes a unit vector as octahedral coordinates in two signed bytes
+///
+/// Points on the folded edges of the square describe the same direction twice, so decoding one of
+/// them and encoding it again may give its twin.
+pub fn encode_octahedral_snorm8(n: Vector3<f32>) -> Vector2<i8> {
+	let e = encode_octahedral(n);
+	Vector2::new(encode_snorm8(e.x), encode_snorm8(e.y))
+}
+
+/// Decodes a unit vector from octahedral coordinates in two signed bytes
+pub fn decode_octahedral_snorm8(e: Vector2<i8>) -> Vector3<f32> {
+	decode_octahedral(Vector2::new(decode_snorm8(e.x), decode_snorm8(e.y)))
+}
+
+/// Encodes a unit vector as octahedral coordinates in two signed 16-bit integers
+///
+/// Points on the folded edges of the square alias as with [`encode_octahedral_snorm8`].
+pub fn encode_octahedral_snorm16(n: Vector3<f32>) -> Vector2<i16> {
+	let e = encode_octahedral(n);
+	Vector2::new(encode_snorm16(e.x), encode_snorm16(e.y))
+}
+
+/// Decodes a unit vector from octahedral coordinates in two signed 16-bit integers
+pub fn decode_octahedral_snorm16(e: Vector2<i16>) -> Vector3<f32> {
+	decode_octahedral(Vector2::new(decode_snorm16(e.x), decode_snorm16(e.y)))
+}
+
+/// Packs skin weights into bytes that sum to exactly 255
+///
+/// Negative weights count as zero. The rest are quantized as by [`SkinWeights::quantize_u8`],
+/// keeping their positions, and weights that sum to zero pack as zeros.
+pub fn encode_weights_unorm8(weights: &[f32]) -> Vec<u8> {
+	let skin = SkinWeights {
+		influences: weights.iter()
+			.enumerate()
+			.map(|(i, w)| Influence { joint: i, weight: w.max(0.0) })
+			.collect(),
+	};
+
+	skin.quantize_u8()
+}
+
+/// Decodes an M3 normal or tangent, stored as bytes mapping `[0, 255]` onto `[-1, 1]`
+pub fn decode_m3_vector(v: Vector3<u8>) -> Vector3<f32> {
+	v.map(|c| f32::from(c) / 255.0 * 2.0 - 1.0)
+}
+
+/// Encodes an M3 normal or tangent, clamping components outside `[-1, 1]`
+pub fn encode_m3_vector(v: Vector3<f32>) -> Vector3<u8> {
+	v.map(|c| ((c.clamp(-1.0, 1.0) + 1.0) / 2.0 * 255.0).round() as u8)
+}
+
+/// Decodes the M3 sign byte that follows the normal into the tangent's bitangent sign
+///
+/// The byte uses the same mapping as the normal, so 0 is -1 and 255 is 1. Only those two are
+/// written; other bytes are read as the nearer of them.
+pub fn decode_m3_sign(v: u8) -> f32 {
+	if v >= 128 { 1.0 } else { -1.0 }
+}
+
+/// Encodes a bitangent sign into the M3 sign byte
+pub fn encode_m3_sign(sign: f32) -> u8 {
+	if sign >= 0.0 { 255 } else { 0 }
+}
+
+/// Decodes an M3 texture coordinate, stored in units of 1/2048
+///
+/// This is the mapping with the default UVW multiplier of 16 and offset of 0; the V axis is
+/// left as stored.
+pub fn decode_m3_uv(v: Vector2<i16>) -> Vector2<f32> {
+	v.map(|c| f32::from(c) / 2048.0)
+}
+
+/// Encodes an M3 texture coordinate, clamping values outside `[-16, 16)`
+pub fn encode_m3_uv(v: Vector2<f32>) -> Vector2<i16> {
+	v.map(|c| (c * 2048.0).round().clamp(-32768.0, 32767.0) as i16)
+}
+
+/// Decodes an ism2 normal or tangent, stored as three half precision floats
+pub fn decode_ism2_vector(v: Vector3<u16>) -> Vector3<f32> {
+	v.map(decode_half)
+}
+
+/// Encodes an ism2 normal or tangent as three half precision floats
+pub fn encode_ism2_vector(v: Vector3<f32>) -> Vector3<u16> {
+	v.map(encode_half)
+}
+
+/// Decodes an ism2 texture coordinate, stored as two half precision floats
+pub fn decode_ism2_uv(v: Vector2<u16>) -> Vector2<f32> {
+	v.map(decode_half)
+}
+
+/// Encodes an ism2 texture coordinate as two half precision floats
+pub fn encode_ism2_uv(v: Vector2<f32>) -> Vector2<u16> {
+	v.map(encode_half)
+}
@@ -1,18 +1,19 @@
+use cgmath::BaseFloat;
+
 use nom::{
 	do_parse,
 	named,
 	number::complete::{
 		be_f32,
-		be_f64,
 		be_u8,
 		le_f32,
-		le_f64,
 		le_u8
 	}
 };
 
 /// A color with red, green, blue, and alpha values
-#[derive(Clone,Debug,Default,PartialEq)]
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Color<T> {
 	pub red: T,
 	pub green: T,
@@ -29,6 +30,198 @@ pub type ColorD = Color<f64>;
 /// Type alias for a [`Color`] of bytes
 pub type ColorB = Color<u8>;
 
+/// A color as hue, saturation and value, with hue in degrees in `[0, 360)`
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Hsv<T> {
+	pub hue: T,
+	pub saturation: T,
+	pub value: T,
+}
+
+impl ColorB {
+	/// Unpacks a color whose components run from most to least significant byte as R, G, B, A
+	///
+	/// The big endian bytes of such a value are laid out as [`le_rgba_b`] reads them.
+	pub fn from_rgba_u32(v: u32) -> ColorB {
+		let [r, g, b, a] = v.to_be_bytes();
+		ColorB { red: r, green: g, blue: b, alpha: a }
+	}
+
+	/// Unpacks a color whose components run from most to least significant byte as A, R, G, B
+	///
+	/// The big endian bytes of such a value are laid out as [`le_argb_b`] and [`be_argb_b`]
+	/// read them.
+	pub fn from_argb_u32(v: u32) -> ColorB {
+		let [a, r, g, b] = v.to_be_bytes();
+		ColorB { red: r, green: g, blue: b, alpha: a }
+	}
+
+	/// Unpacks a color whose components run from most to least significant byte as B, G, R, A
+	///
+	/// The big endian bytes of such a value are laid out as [`le_bgra_b`] and [`be_bgra_b`]
+	/// read them.
+	pub fn from_bgra_u32(v: u32) -> ColorB {
+		let [b, g, r, a] = v.to_be_bytes();
+		ColorB { red: r, green: g, blue: b, alpha: a }
+	}
+
+	/// Unpacks a color whose components run from most to least significant byte as A, B, G, R
+	///
+	/// This is how RGBA bytes read as a little endian `u32` end up, as in Direct3D's
+	/// `R8G8B8A8` formats.
+	pub fn from_abgr_u32(v: u32) -> ColorB {
+		let [a, b, g, r] = v.to_be_bytes();
+		ColorB { red: r, green: g, blue: b, alpha: a }
+	}
+
+	/// Packs the color as R, G, B, A from most to least significant byte
+	pub fn to_rgba_u32(&self) -> u32 {
+		u32::from_be_bytes([self.red, self.green, self.blue, self.alpha])
+	}
+
+	/// Packs the color as A, R, G, B from most to least significant byte
+	pub fn to_argb_u32(&self) -> u32 {
+		u32::from_be_bytes([self.alpha, self.red, self.green, self.blue])
+	}
+
+	/// Packs the color as B, G, R, A from most to least significant byte
+	pub fn to_bgra_u32(&self) -> u32 {
+		u32::from_be_bytes([self.blue, self.green, self.red, self.alpha])
+	}
+
+	/// Packs the color as A, B, G, R from most to least significant byte
+	pub fn to_abgr_u32(&self) -> u32 {
+		u32::from_be_bytes([self.alpha, self.blue, self.green, self.red])
+	}
+}
+
+/// Decodes an sRGB transfer encoded component
+fn srgb_to_linear<T: BaseFloat>(c: T) -> T {
+	if c <= T::from(0.04045).unwrap() {
+		c / T::from(12.92).unwrap()
+	} else {
+		((c + T::from(0.055).unwrap()) / T::from(1.055).unwrap()).powf(T::from(2.4).unwrap())
+	}
+}
+
+/// Applies the sRGB transfer encoding to a linear component
+fn linear_to_srgb<T: BaseFloat>(c: T) -> T {
+	if c <= T::from(0.0031308).unwrap() {
+		c * T::from(12.92).unwrap()
+	} else {
+		T::from(1.055).unwrap() * c.powf(T::one() / T::from(2.4).unwrap()) - T::from(0.055).unwrap()
+	}
+}
+
+impl<T: BaseFloat> Color<T> {
+	/// Converts bytes to components in `[0, 1]`, without changing the color space
+	pub fn from_bytes(c: &ColorB) -> Color<T> {
+		let f = |b: u8| T::from(b).unwrap() / T::from(255).unwrap();
+		Color { red: f(c.red), green: f(c.green), blue: f(c.blue), alpha: f(c.alpha) }
+	}
+
+	/// Converts components to bytes, clamping them to `[0, 1]` and rounding to the nearest
+	/// step, without changing the color space
+	pub fn to_bytes(&self) -> ColorB {
+		let b = |c: T| (c.max(T::zero()).min(T::one()) * T::from(255).unwrap()).round().to_u8().unwrap_or(0);
+		ColorB { red: b(self.red), green: b(self.green), blue: b(self.blue), alpha: b(self.alpha) }
+	}
+
+	/// Decodes sRGB components to linear light, leaving alpha unchanged
+	///
+	/// Byte colors and colors picked in editors are usually sRGB, while lighting and blending
+	/// should happen in linear light. Treating one as the other makes colors look washed out
+	/// or too dark.
+	pub fn to_linear(&self) -> Color<T> {
+		Color {
+			red: srgb_to_linear(self.red),
+			green: srgb_to_linear(self.green),
+			blue: srgb_to_linear(self.blue),
+			alpha: self.alpha,
+		}
+	}
+
+	/// Encodes linear components as sRGB, leaving alpha unchanged
+	pub fn to_srgb(&self) -> Color<T> {
+		Color {
+			red: linear_to_srgb(self.red),
+			green: linear_to_srgb(self.green),
+			blue: linear_to_srgb(self.blue),
+			alpha: self.alpha,
+		}
+	}
+
+	/// Multiplies the color components by alpha
+	pub fn premultiply(&self) -> Color<T> {
+		Color {
+			red: self.red * self.alpha,
+			green: self.green * self.alpha,
+			blue: self.blue * self.alpha,
+			alpha: self.alpha,
+		}
+	}
+
+	/// Divides premultiplied color components by alpha, giving black if alpha is zero
+	pub fn unpremultiply(&self) -> Color<T> {
+		if self.alpha == T::zero() {
+			return Color { red: T::zero(), green: T::zero(), blue: T::zero(), alpha: T::zero() };
+		}
+		Color {
+			red: self.red / self.alpha,
+			green: self.green / self.alpha,
+			blue: self.blue / self.alpha,
+			alpha: self.alpha,
+		}
+	}
+
+	/// Creates a color from hue, saturation and value, in the same space as the result
+	pub fn from_hsv(hsv: Hsv<T>, alpha: T) -> Color<T> {
+		let sixty = T::from(60).unwrap();
+		let six = T::from(6).unwrap();
+		let h = (hsv.hue / sixty) % six;
+		let h = if h < T::zero() { h + six } else { h };
+		let chroma = hsv.value * hsv.saturation;
+		let x = chroma * (T::one() - ((h % (T::one() + T::one())) - T::one()).abs());
+		let m = hsv.value - chroma;
+		let z = T::zero();
+		let (r, g, b) = match h.to_u8().unwrap_or(0) {
+			0 => (chroma, x, z),
+			1 => (x, chroma, z),
+			2 => (z, chroma, x),
+			3 => (z, x, chroma),
+			4 => (x, z, chroma),
+			_ => (chroma, z, x),
+		};
+
+		Color { red: r + m, green: g + m, blue: b + m, alpha: alpha }
+	}
+
+	/// Returns the hue, saturation and value of the color, with a hue of zero for grays
+	pub fn to_hsv(&self) -> Hsv<T> {
+		let max = self.red.max(self.green).max(self.blue);
+		let min = self.red.min(self.green).min(self.blue);
+		let chroma = max - min;
+		let sixty = T::from(60).unwrap();
+		let hue = if chroma == T::zero() {
+			T::zero()
+		} else if max == self.red {
+			let h = sixty * ((self.green - self.blue) / chroma);
+			if h < T::zero() { h + T::from(360).unwrap() } else { h }
+		} else if max == self.green {
+			sixty * ((self.blue - self.red) / chroma + T::from(2).unwrap())
+		} else {
+			sixty * ((self.red - self.green) / chroma + T::from(4).unwrap())
+		};
+
+		Hsv {
+			hue: hue,
+			saturation: if max == T::zero() { T::zero() } else { chroma / max },
+			value: max,
+		}
+	}
+}
+
 /// Parses a ['ColorB'] as little endian RGBA
 named!(pub le_rgba_b<ColorB>,
 	do_parse!(
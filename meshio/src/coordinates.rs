@@ -0,0 +1,385 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Matrix,
+	Matrix3,
+	Matrix4,
+	Quaternion,
+	SquareMatrix,
+	Vector3
+};
+
+use std::{
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	}
+};
+
+use crate::{
+	animation::Track,
+	physics::{
+		Limits,
+		Shape
+	},
+	Face,
+	Scene,
+	Transform
+};
+
+/// A signed coordinate axis
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum Axis {
+	PositiveX,
+	NegativeX,
+	PositiveY,
+	NegativeY,
+	PositiveZ,
+	NegativeZ,
+}
+
+impl Axis {
+	/// Returns the unit vector along the axis
+	pub fn vector<T: BaseFloat>(self) -> Vector3<T> {
+		let (o, z) = (T::one(), T::zero());
+		match self {
+			Axis::PositiveX => Vector3::new(o, z, z),
+			Axis::NegativeX => Vector3::new(-o, z, z),
+			Axis::PositiveY => Vector3::new(z, o, z),
+			Axis::NegativeY => Vector3::new(z, -o, z),
+			Axis::PositiveZ => Vector3::new(z, z, o),
+			Axis::NegativeZ => Vector3::new(z, z, -o),
+		}
+	}
+}
+
+/// Whether the X, Y and Z axes follow the right or left hand rule
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum Handedness {
+	Left,
+	Right,
+}
+
+/// The axes and unit a [`Scene`] is expressed in
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct CoordinateSystem {
+	pub up: Axis,
+	/// Direction the front of a model faces
+	pub forward: Axis,
+	pub handedness: Handedness,
+	/// Length of one unit in meters
+	pub meters_per_unit: f64,
+}
+
+impl Default for CoordinateSystem {
+	fn default() -> CoordinateSystem {
+		CoordinateSystem::GLTF
+	}
+}
+
+impl CoordinateSystem {
+	/// Right-handed, Y up and +Z forward in meters, as used by glTF and most OBJ consumers
+	pub const GLTF: CoordinateSystem = CoordinateSystem {
+		up: Axis::PositiveY,
+		forward: Axis::PositiveZ,
+		handedness: Handedness::Right,
+		meters_per_unit: 1.0,
+	};
+
+	/// Left-handed, Y up and -Z forward in units of 8 cm, as used by MikuMikuDance
+	pub const MMD: CoordinateSystem = CoordinateSystem {
+		up: Axis::PositiveY,
+		forward: Axis::NegativeZ,
+		handedness: Handedness::Left,
+		meters_per_unit: 0.08,
+	};
+
+	/// Right-handed, Z up and +X forward in inches, as used by the Source engine
+	pub const SOURCE: CoordinateSystem = CoordinateSystem {
+		up: Axis::PositiveZ,
+		forward: Axis::PositiveX,
+		handedness: Handedness::Right,
+		meters_per_unit: 0.0254,
+	};
+
+	/// Right-handed, Z up and +X forward, as used by Blizzard's MDX and M3 models
+	///
+	/// Game units have no fixed real-world size, so they are treated as meters.
+	pub const BLIZZARD: CoordinateSystem = CoordinateSystem {
+		up: Axis::PositiveZ,
+		forward: Axis::PositiveX,
+		handedness: Handedness::Right,
+		meters_per_unit: 1.0,
+	};
+
+	/// Returns true if the up and forward axes are perpendicular
+	pub fn is_valid(&self) -> bool {
+		self.up.vector::<f64>().dot(self.forward.vector()) == 0.0
+	}
+
+	/// Returns the matrix whose columns are the model's right, up and forward directions
+	fn basis<T: BaseFloat>(&self) -> Matrix3<T> {
+		let (up, forward) = (self.up.vector::<T>(), self.forward.vector::<T>());
+		let right = match self.handedness {
+			Handedness::Right => forward.cross(up),
+			Handedness::Left => up.cross(forward),
+		};
+		Matrix3::from_cols(right, up, forward)
+	}
+
+	/// Returns the rotation or reflection that maps directions in this system to `target`
+	pub fn reorientation<T: BaseFloat>(&self, target: &CoordinateSystem) -> Matrix3<T> {
+		target.basis() * self.basis::<T>().transpose()
+	}
+
+	/// Returns the factor that converts lengths in this system to `target`
+	pub fn scale_to<T: BaseFloat>(&self, target: &CoordinateSystem) -> T {
+		T::from(self.meters_per_unit / target.meters_per_unit).unwrap()
+	}
+}
+
+/// A [`CoordinateSystem`] whose up and forward axes are not perpendicular
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct InvalidCoordinateSystem(pub CoordinateSystem);
+
+impl Display for InvalidCoordinateSystem {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "up axis {:?} and forward axis {:?} are not perpendicular", self.0.up, self.0.forward)
+	}
+}
+
+impl error::Error for InvalidCoordinateSystem {}
+
+/// Applies a signed axis permutation and uniform scale to every kind of scene data
+struct Converter<T> {
+	axes: Matrix3<T>,
+	scale: T,
+	mirrored: bool,
+}
+
+impl<T: BaseFloat> Converter<T> {
+	fn point(&self, v: Vector3<T>) -> Vector3<T> {
+		self.axes * v * self.scale
+	}
+
+	fn direction(&self, v: Vector3<T>) -> Vector3<T> {
+		self.axes * v
+	}
+
+	/// Permutes per-axis magnitudes such as scales and half extents
+	fn magnitudes(&self, v: Vector3<T>) -> Vector3<T> {
+		let a = self.axes;
+		let abs = Matrix3::from_cols(
+			Vector3::new(a.x.x.abs(), a.x.y.abs(), a.x.z.abs()),
+			Vector3::new(a.y.x.abs(), a.y.y.abs(), a.y.z.abs()),
+			Vector3::new(a.z.x.abs(), a.z.y.abs(), a.z.z.abs()),
+		);
+		abs * v
+	}
+
+	/// Conjugates a rotation, or a linear combination of rotations such as a tangent, by the
+	/// axis change
+	fn rotation(&self, q: Quaternion<T>) -> Quaternion<T> {
+		let v = self.direction(q.v);
+		Quaternion::from_sv(q.s, if self.mirrored { -v } else { v })
+	}
+
+	fn matrix(&self, m: Matrix4<T>) -> Matrix4<T> {
+		let forward = Matrix4::from(self.axes * self.scale);
+		let back = Matrix4::from(self.axes.transpose() * (T::one() / self.scale));
+		forward * m * back
+	}
+
+	fn transform(&self, t: Transform<T>) -> Transform<T> {
+		Transform {
+			translation: self.point(t.translation),
+			rotation: self.rotation(t.rotation),
+			scale: self.magnitudes(t.scale),
+		}
+	}
+
+	/// Converts the frame of a shape or constraint whose local axes must stay fixed, flipping
+	/// its local X axis when the conversion mirrors so that the rotation stays proper
+	fn frame(&self, t: Transform<T>) -> Transform<T> {
+		let mut axes = self.axes * Matrix3::from(t.rotation);
+
+		if self.mirrored {
+			axes.x = -axes.x;
+		}
+		Transform {
+			translation: self.point(t.translation),
+			rotation: Quaternion::from(axes),
+			scale: t.scale,
+		}
+	}
+
+	/// Returns the rotation that re-expresses a child of a [`frame`](Self::frame) converted node in
+	/// that node's fixed axes
+	fn unframe(&self) -> Matrix3<T> {
+		let mut axes = self.axes;
+
+		if self.mirrored {
+			axes.x = -axes.x;
+		}
+		axes.transpose()
+	}
+
+	fn vector_track(&self, track: &mut Track<T, Vector3<T>>, convert: impl Fn(&Self, Vector3<T>) -> Vector3<T>) {
+		for key in track.keys.iter_mut() {
+			key.value = convert(self, key.value);
+			key.in_tangent = convert(self, key.in_tangent);
+			key.out_tangent = convert(self, key.out_tangent);
+		}
+	}
+
+	/// Negates and swaps the X bounds, for a frame whose local X axis was flipped
+	fn flip_x(limits: &mut Limits<T>) {
+		let (lower, upper) = (limits.lower.x, limits.upper.x);
+		limits.lower.x = -upper;
+		limits.upper.x = -lower;
+	}
+}
+
+impl<P: BaseFloat, C> Scene<P, C> {
+	/// Re-expresses the scene in `target` and records it as the scene's coordinate system
+	///
+	/// Positions, normals, tangents, joints, animation tracks, morphs, scene nodes, cameras,
+	/// lights and physics are converted together, and face winding is reversed when the
+	/// handedness changes so that front faces stay in front.
+	///
+	/// Lights and cameras look along their node's local -Z axis, so their nodes keep fixed local
+	/// axes the way rigid bodies do and still point the same way afterwards. A node that also
+	/// draws an object is converted for the object instead.
+	///
+	/// Fails without changing the scene if either coordinate system is invalid.
+	pub fn convert_to(&mut self, target: CoordinateSystem) -> Result<(), InvalidCoordinateSystem> {
+		if !self.coordinate_system.is_valid() {
+			return Err(InvalidCoordinateSystem(self.coordinate_system));
+		}
+		if !target.is_valid() {
+			return Err(InvalidCoordinateSystem(target));
+		}
+
+		let axes: Matrix3<P> = self.coordinate_system.reorientation(&target);
+		let c = Converter {
+			axes: axes,
+			scale: self.coordinate_system.scale_to(&target),
+			mirrored: axes.determinant() < P::zero(),
+		};
+
+		for object in self.objects.iter_mut() {
+			for node in object.nodes.iter_mut() {
+				for v in node.vertices.iter_mut() {
+					v.position = c.point(v.position);
+					v.normals = [c.direction(v.normals[0]), c.direction(v.normals[1])];
+					let t = c.direction(v.tangent.truncate());
+					let w = if c.mirrored { -v.tangent.w } else { v.tangent.w };
+					v.tangent = t.extend(w);
+				}
+
+				if c.mirrored {
+					node.faces = node.faces.iter()
+						.map(|f| {
+							let mut indices = f.indices();
+							indices.reverse();
+							Face::from_indices(indices)
+						})
+						.collect();
+				}
+			}
+
+			if let Some(skeleton) = object.skeleton.as_mut() {
+				for i in 0..skeleton.len() {
+					let joint = skeleton.joint_mut(i).unwrap();
+					joint.translation = c.point(joint.translation);
+					joint.rotation = c.rotation(joint.rotation);
+					joint.scale = c.magnitudes(joint.scale);
+					joint.inverse_bind = c.matrix(joint.inverse_bind);
+				}
+			}
+
+			for clip in object.animations.iter_mut() {
+				for channel in clip.channels.iter_mut() {
+					c.vector_track(&mut channel.translation, Converter::point);
+					c.vector_track(&mut channel.scale, Converter::magnitudes);
+					for key in channel.rotation.keys.iter_mut() {
+						key.value = c.rotation(key.value);
+						key.in_tangent = c.rotation(key.in_tangent);
+						key.out_tangent = c.rotation(key.out_tangent);
+					}
+				}
+			}
+
+			for offset in object.morphs.iter_mut().flat_map(|m| m.offsets.iter_mut()) {
+				offset.position = c.point(offset.position);
+				offset.normal = c.direction(offset.normal);
+			}
+
+			for body in object.physics.bodies.iter_mut() {
+				body.transform = c.frame(body.transform);
+				body.shape = match &body.shape {
+					Shape::Sphere { radius } => Shape::Sphere { radius: *radius * c.scale },
+					Shape::Box { half_extents } => Shape::Box { half_extents: half_extents * c.scale },
+					Shape::Capsule { radius, height } => Shape::Capsule { radius: *radius * c.scale, height: *height * c.scale },
+					Shape::Cylinder { radius, height } => Shape::Cylinder { radius: *radius * c.scale, height: *height * c.scale },
+					Shape::Plane { half_extents } => Shape::Plane { half_extents: half_extents * c.scale },
+					Shape::ConvexHull { points } => Shape::ConvexHull {
+						points: points.iter().map(|p| {
+							let p = p * c.scale;
+							if c.mirrored { Vector3::new(-p.x, p.y, p.z) } else { p }
+						}).collect(),
+					},
+				};
+			}
+
+			for constraint in object.physics.constraints.iter_mut() {
+				constraint.transform = c.frame(constraint.transform);
+				constraint.linear.lower *= c.scale;
+				constraint.linear.upper *= c.scale;
+
+				if c.mirrored {
+					// Flipping local X reverses the sense of rotations around Y and Z
+					Converter::flip_x(&mut constraint.linear);
+					let angular = &mut constraint.angular;
+					let (lower, upper) = (angular.lower, angular.upper);
+					angular.lower = Vector3::new(lower.x, -upper.y, -upper.z);
+					angular.upper = Vector3::new(upper.x, -lower.y, -lower.z);
+				}
+			}
+		}
+
+		let framed: Vec<bool> = self.nodes.iter()
+			.map(|n| n.object.is_none() && (n.light.is_some() || n.camera.is_some()))
+			.collect();
+		let unframe = c.unframe();
+
+		for (i, node) in self.nodes.iter_mut().enumerate() {
+			let mut t = if framed[i] { c.frame(node.transform) } else { c.transform(node.transform) };
+
+			// Nodes following a joint are placed in the joint's axes, which convert normally
+			if node.joint.is_none() && node.parent.and_then(|p| framed.get(p).cloned()).unwrap_or(false) {
+				t.translation = unframe * t.translation;
+				t.rotation = Quaternion::from(unframe) * t.rotation;
+			}
+			node.transform = t;
+		}
+
+		for light in self.lights.iter_mut() {
+			light.attenuation = light.attenuation.take().map(|r| r.start * c.scale..r.end * c.scale);
+		}
+
+		for camera in self.cameras.iter_mut() {
+			camera.near *= c.scale;
+			camera.far *= c.scale;
+			camera.target = camera.target.map(|t| c.point(t));
+		}
+
+		self.coordinate_system = target;
+		Ok(())
+	}
+}
@@ -0,0 +1,166 @@
+use std::{
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	},
+	io,
+	result
+};
+
+use nom::{
+	error::ErrorKind,
+	Err
+};
+
+use crate::Format;
+
+/// Location in the input where an error was detected
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Position {
+	/// Zero-based byte offset, used for binary formats
+	Offset(usize),
+	/// One-based line and column, used for text formats
+	LineColumn {
+		line: usize,
+		column: usize,
+	},
+}
+
+impl Position {
+	/// Converts a byte offset into `input` to a line and column
+	pub fn line_column(input: &[u8], offset: usize) -> Position {
+		let head = &input[..offset.min(input.len())];
+		let line_start = head.iter().rposition(|&b| b == b'\n').map_or(0, |p| p + 1);
+
+		Position::LineColumn {
+			line: head.iter().filter(|&&b| b == b'\n').count() + 1,
+			column: head.len() - line_start + 1,
+		}
+	}
+}
+
+impl Display for Position {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Position::Offset(offset) => write!(f, "byte {}", offset),
+			Position::LineColumn { line, column } => write!(f, "line {}, column {}", line, column),
+		}
+	}
+}
+
+/// Errors produced while importing or exporting a scene
+#[derive(Debug)]
+pub enum Error {
+	/// Reading or writing the underlying stream failed
+	Io(io::Error),
+	/// No known format matched the input
+	UnknownFormat,
+	/// Scenes cannot be written in the format
+	NoExporter(Format),
+	/// The input is of a known format, but its version is not supported
+	UnsupportedVersion {
+		format: Format,
+		version: String,
+	},
+	/// The input ended in the middle of `context`
+	UnexpectedEof {
+		format: Format,
+		context: String,
+		position: Position,
+	},
+	/// The input contains invalid data in `context`
+	Corrupt {
+		format: Format,
+		context: String,
+		position: Position,
+		cause: String,
+	},
+}
+
+/// Type alias for a result with a MeshIO [`Error`]
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+	/// Converts a nom error from parsing `input` in a binary format
+	///
+	/// `context` names the structure being parsed, such as "bone table".
+	pub fn parse(format: Format, context: &str, input: &[u8], err: Err<(&[u8], ErrorKind)>) -> Error {
+		Error::from_nom(format, context, input, err, Position::Offset)
+	}
+
+	/// Converts a nom error from parsing `input` in a text format, reporting lines and columns
+	pub fn parse_text(format: Format, context: &str, input: &[u8], err: Err<(&[u8], ErrorKind)>) -> Error {
+		Error::from_nom(format, context, input, err, |offset| Position::line_column(input, offset))
+	}
+
+	fn from_nom<F>(format: Format, context: &str, input: &[u8], err: Err<(&[u8], ErrorKind)>,
+		position: F) -> Error where F: Fn(usize) -> Position {
+		match err {
+			Err::Incomplete(_) | Err::Error((_, ErrorKind::Eof)) | Err::Failure((_, ErrorKind::Eof)) =>
+				Error::UnexpectedEof {
+					format: format,
+					context: context.to_owned(),
+					position: position(input.len()),
+				},
+			Err::Error((rest, kind)) | Err::Failure((rest, kind)) => Error::Corrupt {
+				format: format,
+				context: context.to_owned(),
+				position: position(input.len() - rest.len()),
+				cause: kind.description().to_lowercase(),
+			},
+		}
+	}
+
+	/// Returns the format the error occurred in, if known
+	pub fn format(&self) -> Option<Format> {
+		match self {
+			Error::UnsupportedVersion { format, .. } |
+			Error::UnexpectedEof { format, .. } |
+			Error::Corrupt { format, .. } |
+			Error::NoExporter(format) => Some(*format),
+			_ => None,
+		}
+	}
+
+	/// Returns where in the input the error occurred, if known
+	pub fn position(&self) -> Option<Position> {
+		match self {
+			Error::UnexpectedEof { position, .. } |
+			Error::Corrupt { position, .. } => Some(*position),
+			_ => None,
+		}
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Error::Io(e) => e.fmt(f),
+			Error::UnknownFormat => write!(f, "unrecognized file format"),
+			Error::NoExporter(format) => write!(f, "{} files cannot be written", format.name()),
+			Error::UnsupportedVersion { format, version } =>
+				write!(f, "unsupported {} version {}", format.name(), version),
+			Error::UnexpectedEof { format, context, position } =>
+				write!(f, "unexpected end of input in {} {} at {}", format.name(), context, position),
+			Error::Corrupt { format, context, position, cause } =>
+				write!(f, "invalid {} {} at {}: {}", format.name(), context, position, cause),
+		}
+	}
+}
+
+impl error::Error for Error {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Error::Io(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Error {
+		Error::Io(e)
+	}
+}
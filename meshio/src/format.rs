@@ -0,0 +1,228 @@
+use std::{
+	ffi::OsStr,
+	io::{
+		Read,
+		Write
+	},
+	path::Path
+};
+
+use crate::{
+	CoordinateSystem,
+	Error,
+	Result,
+	SceneF
+};
+
+/// File formats that MeshIO can recognize
+///
+/// Every variant can be detected, but so far only PMX and OBJ have an [`Importer`] and only OBJ
+/// an [`Exporter`]; the other format crates are drafts that do not build against this core yet.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Format {
+	Ase,
+	M3,
+	Mdl,
+	Mdx,
+	Obj,
+	Pmd,
+	Pmx,
+	Stl,
+	ValveMdl,
+	Xps,
+	XpsAscii,
+}
+
+impl Format {
+	/// Every known format, in the order signatures are tested
+	pub const ALL: [Format; 11] = [
+		Format::Pmx,
+		Format::Pmd,
+		Format::Mdx,
+		Format::M3,
+		Format::ValveMdl,
+		Format::Xps,
+		Format::Ase,
+		Format::Mdl,
+		Format::Obj,
+		Format::Stl,
+		Format::XpsAscii,
+	];
+
+	/// Returns a short human readable name for the format
+	pub fn name(self) -> &'static str {
+		match self {
+			Format::Ase => "ASE",
+			Format::M3 => "M3",
+			Format::Mdl => "MDL",
+			Format::Mdx => "MDX",
+			Format::Obj => "OBJ",
+			Format::Pmd => "PMD",
+			Format::Pmx => "PMX",
+			Format::Stl => "STL",
+			Format::ValveMdl => "Valve MDL",
+			Format::Xps => "XPS",
+			Format::XpsAscii => "XPS ASCII",
+		}
+	}
+
+	/// Returns the lowercase file extensions commonly used by the format
+	pub fn extensions(self) -> &'static [&'static str] {
+		match self {
+			Format::Ase => &["ase"],
+			Format::M3 => &["m3"],
+			Format::Mdl | Format::ValveMdl => &["mdl"],
+			Format::Mdx => &["mdx"],
+			Format::Obj => &["obj"],
+			Format::Pmd => &["pmd"],
+			Format::Pmx => &["pmx"],
+			Format::Stl => &["stl"],
+			Format::Xps => &["xps", "mesh"],
+			Format::XpsAscii => &["ascii"],
+		}
+	}
+
+	/// Returns the signatures any file of the format starts with, if it has one
+	pub fn signatures(self) -> &'static [&'static [u8]] {
+		match self {
+			Format::Ase => &[b"*3DSMAX_ASCIIEXPORT"],
+			Format::M3 => &[b"43DM", b"33DM"],
+			Format::Mdx => &[b"MDLX"],
+			Format::Pmd => &[b"Pmd"],
+			Format::Pmx => &[b"PMX "],
+			Format::ValveMdl => &[b"IDST"],
+			Format::Xps => &[&[160, 238, 4, 0]],
+			_ => &[],
+		}
+	}
+
+	/// Returns the coordinate system the format's files are authored in
+	pub fn coordinate_system(self) -> CoordinateSystem {
+		match self {
+			Format::Pmd | Format::Pmx => CoordinateSystem::MMD,
+			Format::M3 | Format::Mdl | Format::Mdx => CoordinateSystem::BLIZZARD,
+			Format::ValveMdl => CoordinateSystem::SOURCE,
+			_ => CoordinateSystem::GLTF,
+		}
+	}
+
+	/// Returns true if `bytes` starts with one of the format's signatures
+	pub fn matches(self, bytes: &[u8]) -> bool {
+		self.signatures().iter().any(|s| bytes.starts_with(s))
+	}
+}
+
+/// Detects the format of a file from its signature, falling back to the extension of `path`
+///
+/// Signatures always win over the extension, so mislabeled binary files are still identified.
+/// When nothing matches, formats without a signature are preferred for the extension, which
+/// makes a `.mdl` file lacking Valve's `IDST` tag resolve to Blizzard's text MDL.
+pub fn detect(bytes: &[u8], path: Option<&Path>) -> Option<Format> {
+	if let Some(format) = Format::ALL.iter().find(|f| f.matches(bytes)) {
+		return Some(*format);
+	}
+
+	let ext = path
+		.and_then(Path::extension)
+		.and_then(OsStr::to_str)
+		.map(str::to_ascii_lowercase)?;
+
+	Format::ALL.iter()
+		.find(|f| f.signatures().is_empty() && f.extensions().contains(&ext.as_str()))
+		.or_else(|| Format::ALL.iter().find(|f| f.extensions().contains(&ext.as_str())))
+		.cloned()
+}
+
+/// A file format that can be read into a [`SceneF`]
+pub trait Importer {
+	/// Returns the format this importer reads
+	fn format(&self) -> Format;
+
+	/// Imports a scene from a complete file held in memory
+	fn import(&self, input: &[u8]) -> Result<SceneF>;
+
+	/// Imports a scene from a reader, buffering it entirely before parsing
+	fn import_from(&self, reader: &mut dyn Read) -> Result<SceneF> {
+		let mut buf = Vec::new();
+		reader.read_to_end(&mut buf)?;
+		self.import(&buf)
+	}
+}
+
+/// A file format that a [`SceneF`] can be written to
+pub trait Exporter {
+	/// Returns the format this exporter writes
+	fn format(&self) -> Format;
+
+	/// Exports a scene into a newly allocated buffer
+	fn export(&self, scene: &SceneF) -> Result<Vec<u8>>;
+
+	/// Exports a scene into a writer
+	fn export_to(&self, scene: &SceneF, writer: &mut dyn Write) -> Result<()> {
+		writer.write_all(&self.export(scene)?)?;
+		Ok(())
+	}
+}
+
+/// A collection of importers selected by [`detect`], and of exporters selected by format
+#[derive(Default)]
+pub struct Registry {
+	importers: Vec<Box<dyn Importer>>,
+	exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl Registry {
+	/// Creates an empty registry
+	pub fn new() -> Registry {
+		Registry::default()
+	}
+
+	/// Adds an importer, replacing any previously registered for the same format
+	pub fn register<I: Importer + 'static>(&mut self, importer: I) {
+		let format = importer.format();
+		self.importers.retain(|i| i.format() != format);
+		self.importers.push(Box::new(importer));
+	}
+
+	/// Adds an exporter, replacing any previously registered for the same format
+	pub fn register_exporter<E: Exporter + 'static>(&mut self, exporter: E) {
+		let format = exporter.format();
+		self.exporters.retain(|e| e.format() != format);
+		self.exporters.push(Box::new(exporter));
+	}
+
+	/// Returns the importer registered for `format`
+	pub fn importer(&self, format: Format) -> Option<&dyn Importer> {
+		self.importers.iter()
+			.find(|i| i.format() == format)
+			.map(|i| i.as_ref())
+	}
+
+	/// Returns the registered importer for the detected format of a file
+	pub fn detect(&self, bytes: &[u8], path: Option<&Path>) -> Option<&dyn Importer> {
+		detect(bytes, path).and_then(|f| self.importer(f))
+	}
+
+	/// Detects the format of a file and imports it with the matching importer
+	pub fn import(&self, bytes: &[u8], path: Option<&Path>) -> Result<SceneF> {
+		match self.detect(bytes, path) {
+			Some(importer) => importer.import(bytes),
+			None => Err(Error::UnknownFormat),
+		}
+	}
+
+	/// Returns the exporter registered for `format`
+	pub fn exporter(&self, format: Format) -> Option<&dyn Exporter> {
+		self.exporters.iter()
+			.find(|e| e.format() == format)
+			.map(|e| e.as_ref())
+	}
+
+	/// Exports a scene with the exporter registered for `format`
+	pub fn export(&self, scene: &SceneF, format: Format) -> Result<Vec<u8>> {
+		match self.exporter(format) {
+			Some(exporter) => exporter.export(scene),
+			None => Err(Error::NoExporter(format)),
+		}
+	}
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+/// Languages primarily used as keys for [`LocalizedStringMap`]
+#[derive(Clone,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub enum Language {
+	English,
+	Japanese,
+	/// Chinese in simplified characters, as used in mainland China and Singapore
+	ChineseSimplified,
+	/// Chinese in traditional characters, as used in Taiwan, Hong Kong and Macau
+	ChineseTraditional,
+	Korean,
+	/// Any other language, as a BCP 47 tag in canonical case such as `de` or `pt-BR`
+	Other(String),
+}
+
+impl Language {
+	/// Maps a BCP 47 tag to a language, ignoring case and any subtags that do not affect it
+	///
+	/// Chinese tags with a `Hant` script or a `TW`, `HK` or `MO` region are traditional, and
+	/// other Chinese tags are simplified. Other tags are kept with `-` separators and canonical
+	/// case, so `PT_br` and `pt-BR` map to the same language.
+	pub fn from_tag(tag: &str) -> Language {
+		let lower = tag.to_ascii_lowercase();
+		let mut subtags = lower.split(['-', '_']);
+
+		match subtags.next().unwrap_or("") {
+			"en" => Language::English,
+			"ja" => Language::Japanese,
+			"ko" => Language::Korean,
+			"zh" => {
+				let rest: Vec<&str> = subtags.collect();
+				if rest.contains(&"hans") {
+					Language::ChineseSimplified
+				} else if rest.iter().any(|s| ["hant", "tw", "hk", "mo"].contains(s)) {
+					Language::ChineseTraditional
+				} else {
+					Language::ChineseSimplified
+				}
+			},
+			_ => Language::Other(normalize(&lower)),
+		}
+	}
+
+	/// Returns the BCP 47 tag of the language
+	pub fn tag(&self) -> &str {
+		match self {
+			Language::English => "en",
+			Language::Japanese => "ja",
+			Language::ChineseSimplified => "zh-Hans",
+			Language::ChineseTraditional => "zh-Hant",
+			Language::Korean => "ko",
+			Language::Other(tag) => tag,
+		}
+	}
+
+	/// Returns the languages to try in order when a string is missing in this one: itself, the
+	/// other Chinese script for Chinese, then English
+	pub fn fallbacks(&self) -> Vec<Language> {
+		let mut chain = vec![self.clone()];
+
+		match self {
+			Language::ChineseSimplified => chain.push(Language::ChineseTraditional),
+			Language::ChineseTraditional => chain.push(Language::ChineseSimplified),
+			_ => {},
+		}
+		if *self != Language::English {
+			chain.push(Language::English);
+		}
+		chain
+	}
+}
+
+/// Re-cases the subtags of a lowercase tag: scripts in title case, regions in upper case and
+/// everything else, including all subtags after an extension or private use singleton, in lower
+/// case
+fn normalize(lower: &str) -> String {
+	let mut subtags: Vec<String> = Vec::new();
+	let mut extension = false;
+
+	for subtag in lower.split(['-', '_']) {
+		let cased = match subtag.len() {
+			_ if subtags.is_empty() || extension => subtag.to_owned(),
+			1 => {
+				extension = true;
+				subtag.to_owned()
+			},
+			2 => subtag.to_ascii_uppercase(),
+			4 if subtag.is_ascii() => subtag[..1].to_ascii_uppercase() + &subtag[1..],
+			_ => subtag.to_owned(),
+		};
+		subtags.push(cased);
+	}
+	subtags.join("-")
+}
+
+/// Serializes as the language's BCP 47 tag
+#[cfg(feature = "serde")]
+impl serde::Serialize for Language {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.tag())
+	}
+}
+
+/// Deserializes from a BCP 47 tag with [`Language::from_tag`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Language {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Language, D::Error> {
+		let tag = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Ok(Language::from_tag(&tag))
+	}
+}
+
+/// Type alias for a hash map that maps strings by [`Language`]
+pub type LocalizedStringMap = HashMap<Language, String>;
+
+/// Lookups on a [`LocalizedStringMap`]
+pub trait LocalizedStringMapExt {
+	/// Returns the first non-empty string in the languages of `chain`, or if there is none, the
+	/// non-empty string whose language sorts first
+	fn lookup(&self, chain: &[Language]) -> Option<&str>;
+
+	/// Returns the string in `language`, falling back along [`Language::fallbacks`] and then to
+	/// any language
+	fn localized(&self, language: &Language) -> Option<&str> {
+		self.lookup(&language.fallbacks())
+	}
+
+	/// Returns true if the string in any language equals `name`
+	fn matches(&self, name: &str) -> bool;
+}
+
+impl LocalizedStringMapExt for LocalizedStringMap {
+	fn lookup(&self, chain: &[Language]) -> Option<&str> {
+		chain.iter()
+			.filter_map(|l| self.get(l))
+			.find(|s| !s.is_empty())
+			.or_else(|| {
+				self.iter()
+					.filter(|(_, s)| !s.is_empty())
+					.min_by(|a, b| a.0.cmp(b.0))
+					.map(|(_, s)| s)
+			})
+			.map(String::as_str)
+	}
+
+	fn matches(&self, name: &str) -> bool {
+		self.values().any(|n| n == name)
+	}
+}
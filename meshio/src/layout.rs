@@ -0,0 +1,579 @@
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use std::{
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	}
+};
+
+use crate::{
+	codec,
+	ColorF,
+	SkinWeights,
+	Vertex
+};
+
+/// What a vertex attribute means, named after the usual shader semantics
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum Semantic {
+	Position,
+	Normal,
+	Tangent,
+	Bitangent,
+	TexCoord,
+	Color,
+	/// Skeleton joint indices, paired with the [`Semantic::Weights`] attribute of the same index
+	Joints,
+	/// Skin weights, paired with the [`Semantic::Joints`] attribute of the same index
+	Weights,
+	/// An attribute the core vertex has no place for, kept by name
+	Other(String),
+}
+
+impl Semantic {
+	/// Parses an HLSL style semantic name such as `TEXCOORD` or `BLENDINDICES`, ignoring case
+	///
+	/// Unknown names become [`Semantic::Other`].
+	pub fn from_name(name: &str) -> Semantic {
+		match name.to_ascii_uppercase().as_str() {
+			"POSITION" | "SV_POSITION" => Semantic::Position,
+			"NORMAL" => Semantic::Normal,
+			"TANGENT" => Semantic::Tangent,
+			"BINORMAL" | "BITANGENT" => Semantic::Bitangent,
+			"TEXCOORD" => Semantic::TexCoord,
+			"COLOR" => Semantic::Color,
+			"BLENDINDICES" => Semantic::Joints,
+			"BLENDWEIGHT" | "BLENDWEIGHTS" => Semantic::Weights,
+			_ => Semantic::Other(name.to_owned()),
+		}
+	}
+}
+
+impl Display for Semantic {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Semantic::Position => write!(f, "POSITION"),
+			Semantic::Normal => write!(f, "NORMAL"),
+			Semantic::Tangent => write!(f, "TANGENT"),
+			Semantic::Bitangent => write!(f, "BINORMAL"),
+			Semantic::TexCoord => write!(f, "TEXCOORD"),
+			Semantic::Color => write!(f, "COLOR"),
+			Semantic::Joints => write!(f, "BLENDINDICES"),
+			Semantic::Weights => write!(f, "BLENDWEIGHT"),
+			Semantic::Other(name) => write!(f, "{}", name),
+		}
+	}
+}
+
+/// How each component of an attribute is stored
+///
+/// Normalized formats decode to `[0, 1]` or `[-1, 1]`; the plain integer formats decode to their
+/// integer value.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum ComponentFormat {
+	F32,
+	F16,
+	U8,
+	I8,
+	U16,
+	I16,
+	U32,
+	I32,
+	Unorm8,
+	Snorm8,
+	Unorm16,
+	Snorm16,
+}
+
+impl ComponentFormat {
+	/// Returns the size of one component in bytes
+	pub fn size(self) -> usize {
+		match self {
+			ComponentFormat::U8 | ComponentFormat::I8 |
+			ComponentFormat::Unorm8 | ComponentFormat::Snorm8 => 1,
+			ComponentFormat::F16 | ComponentFormat::U16 | ComponentFormat::I16 |
+			ComponentFormat::Unorm16 | ComponentFormat::Snorm16 => 2,
+			ComponentFormat::F32 | ComponentFormat::U32 | ComponentFormat::I32 => 4,
+		}
+	}
+
+	/// Parses a DXGI format name such as `R32G32B32_FLOAT`, returning the component format and count
+	///
+	/// Only formats whose channels share one size are recognized. Components are returned in memory
+	/// order, so `B8G8R8A8_UNORM` decodes blue first.
+	pub fn from_dxgi(name: &str) -> Option<(ComponentFormat, usize)> {
+		let name = name.trim_start_matches("DXGI_FORMAT_");
+		let split = name.find('_')?;
+		let (channels, kind) = (&name[..split], &name[split + 1..]);
+		let sizes: Vec<&str> = channels.split(|c: char| "RGBAX".contains(c)).filter(|s| !s.is_empty()).collect();
+
+		if sizes.is_empty() || sizes.len() > 4 || sizes.iter().any(|&s| s != sizes[0]) {
+			return None;
+		}
+
+		let format = match (sizes[0], kind) {
+			("32", "FLOAT") => ComponentFormat::F32,
+			("16", "FLOAT") => ComponentFormat::F16,
+			("8", "UINT") => ComponentFormat::U8,
+			("8", "SINT") => ComponentFormat::I8,
+			("16", "UINT") => ComponentFormat::U16,
+			("16", "SINT") => ComponentFormat::I16,
+			("32", "UINT") => ComponentFormat::U32,
+			("32", "SINT") => ComponentFormat::I32,
+			("8", "UNORM") => ComponentFormat::Unorm8,
+			("8", "SNORM") => ComponentFormat::Snorm8,
+			("16", "UNORM") => ComponentFormat::Unorm16,
+			("16", "SNORM") => ComponentFormat::Snorm16,
+			_ => return None,
+		};
+
+		Some((format, sizes.len()))
+	}
+
+	fn decode(self, bytes: &[u8], order: ByteOrder) -> f32 {
+		let u16 = || match order {
+			ByteOrder::Little => u16::from_le_bytes([bytes[0], bytes[1]]),
+			ByteOrder::Big => u16::from_be_bytes([bytes[0], bytes[1]]),
+		};
+		let u32 = || match order {
+			ByteOrder::Little => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+			ByteOrder::Big => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+		};
+
+		match self {
+			ComponentFormat::F32 => f32::from_bits(u32()),
+			ComponentFormat::F16 => codec::decode_half(u16()),
+			ComponentFormat::U8 => f32::from(bytes[0]),
+			ComponentFormat::I8 => f32::from(bytes[0] as i8),
+			ComponentFormat::U16 => f32::from(u16()),
+			ComponentFormat::I16 => f32::from(u16() as i16),
+			ComponentFormat::U32 => u32() as f32,
+			ComponentFormat::I32 => u32() as i32 as f32,
+			ComponentFormat::Unorm8 => codec::decode_unorm8(bytes[0]),
+			ComponentFormat::Snorm8 => codec::decode_snorm8(bytes[0] as i8),
+			ComponentFormat::Unorm16 => codec::decode_unorm16(u16()),
+			ComponentFormat::Snorm16 => codec::decode_snorm16(u16() as i16),
+		}
+	}
+
+	fn encode(self, value: f32, bytes: &mut [u8], order: ByteOrder) {
+		let mut u16 = |v: u16| bytes[..2].copy_from_slice(&match order {
+			ByteOrder::Little => v.to_le_bytes(),
+			ByteOrder::Big => v.to_be_bytes(),
+		});
+
+		match self {
+			ComponentFormat::F16 => u16(codec::encode_half(value)),
+			ComponentFormat::U16 => u16(value.round() as u16),
+			ComponentFormat::I16 => u16(value.round() as i16 as u16),
+			ComponentFormat::Unorm16 => u16(codec::encode_unorm16(value)),
+			ComponentFormat::Snorm16 => u16(codec::encode_snorm16(value) as u16),
+			_ => {
+				let mut u32 = |v: u32| bytes[..4].copy_from_slice(&match order {
+					ByteOrder::Little => v.to_le_bytes(),
+					ByteOrder::Big => v.to_be_bytes(),
+				});
+
+				// Float to integer casts saturate, so out of range values clamp
+				match self {
+					ComponentFormat::F32 => u32(value.to_bits()),
+					ComponentFormat::U32 => u32(value.round() as u32),
+					ComponentFormat::I32 => u32(value.round() as i32 as u32),
+					ComponentFormat::U8 => bytes[0] = value.round() as u8,
+					ComponentFormat::I8 => bytes[0] = value.round() as i8 as u8,
+					ComponentFormat::Unorm8 => bytes[0] = codec::encode_unorm8(value),
+					_ => bytes[0] = codec::encode_snorm8(value) as u8,
+				}
+			},
+		}
+	}
+}
+
+/// Byte order of the components in a vertex buffer
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum ByteOrder {
+	Little,
+	Big,
+}
+
+/// One attribute of an interleaved vertex
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct VertexAttribute {
+	pub semantic: Semantic,
+	/// Distinguishes attributes with the same semantic, such as a second texture coordinate
+	pub index: u32,
+	pub format: ComponentFormat,
+	/// Number of components, from one to four
+	pub components: usize,
+	/// Byte offset from the start of the vertex
+	pub offset: usize,
+}
+
+impl VertexAttribute {
+	/// Returns the size of the attribute in bytes
+	pub fn size(&self) -> usize {
+		self.format.size() * self.components
+	}
+}
+
+/// Describes where each attribute lives in an interleaved vertex buffer
+///
+/// Meant for formats that describe their own vertex layout, such as ninjaripper dumps. None of
+/// the importers that build today read such a format.
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct VertexLayout {
+	pub attributes: Vec<VertexAttribute>,
+	/// Distance in bytes between the starts of consecutive vertices
+	pub stride: usize,
+	pub byte_order: ByteOrder,
+}
+
+/// Decoded values of one attribute for every vertex
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Stream {
+	pub semantic: Semantic,
+	pub index: u32,
+	/// Number of values per vertex
+	pub components: usize,
+	/// Values of all vertices, one after the other
+	pub values: Vec<f32>,
+}
+
+impl Stream {
+	/// Creates an empty stream
+	pub fn new(semantic: Semantic, index: u32, components: usize) -> Stream {
+		Stream {
+			semantic: semantic,
+			index: index,
+			components: components,
+			values: Vec::new(),
+		}
+	}
+
+	/// Returns the number of vertices
+	pub fn len(&self) -> usize {
+		self.values.len().checked_div(self.components).unwrap_or(0)
+	}
+
+	/// Returns true if the stream holds no vertices
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the values of `vertex`
+	pub fn get(&self, vertex: usize) -> &[f32] {
+		&self.values[vertex * self.components..(vertex + 1) * self.components]
+	}
+
+	/// Appends a vertex, truncating or zero-padding `values` to the component count
+	pub fn push(&mut self, values: &[f32]) {
+		let start = self.values.len();
+		self.values.extend(values.iter().take(self.components));
+		self.values.resize(start + self.components, 0.0);
+	}
+
+	/// Returns the values of `vertex` as a vector, filling missing components from `fill`
+	pub fn vector4(&self, vertex: usize, fill: Vector4<f32>) -> Vector4<f32> {
+		let mut v = fill;
+		self.get(vertex).iter().take(4).enumerate().for_each(|(i, &c)| v[i] = c);
+		v
+	}
+}
+
+/// Problems that prevent a layout from encoding or decoding a buffer
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum LayoutError {
+	/// An attribute has no components or more than four
+	ComponentCount {
+		attribute: usize,
+		components: usize,
+	},
+	/// An attribute extends past the end of the vertex stride
+	OutsideStride {
+		attribute: usize,
+	},
+	/// The buffer holds fewer bytes than the vertices need
+	BufferTooShort {
+		needed: usize,
+		len: usize,
+	},
+	/// No stream was given for an attribute of the layout
+	MissingStream {
+		semantic: Semantic,
+		index: u32,
+	},
+	/// A stream holds a different number of vertices than the first stream
+	StreamLength {
+		semantic: Semantic,
+		index: u32,
+	},
+}
+
+impl Display for LayoutError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			LayoutError::ComponentCount { attribute, components } =>
+				write!(f, "attribute {} has {} components", attribute, components),
+			LayoutError::OutsideStride { attribute } => write!(f, "attribute {} extends past the stride", attribute),
+			LayoutError::BufferTooShort { needed, len } =>
+				write!(f, "buffer of {} bytes is shorter than the {} needed", len, needed),
+			LayoutError::MissingStream { semantic, index } => write!(f, "no stream for {}{}", semantic, index),
+			LayoutError::StreamLength { semantic, index } =>
+				write!(f, "stream {}{} has a different vertex count", semantic, index),
+		}
+	}
+}
+
+impl error::Error for LayoutError {}
+
+impl VertexLayout {
+	/// Creates a layout with no attributes
+	pub fn new(byte_order: ByteOrder) -> VertexLayout {
+		VertexLayout {
+			attributes: Vec::new(),
+			stride: 0,
+			byte_order: byte_order,
+		}
+	}
+
+	/// Appends an attribute at the end of the vertex, growing the stride, and returns its offset
+	pub fn push(&mut self, semantic: Semantic, index: u32, format: ComponentFormat, components: usize) -> usize {
+		let offset = self.stride;
+
+		self.attributes.push(VertexAttribute {
+			semantic: semantic,
+			index: index,
+			format: format,
+			components: components,
+			offset: offset,
+		});
+		self.stride += format.size() * components;
+		offset
+	}
+
+	/// Returns the attribute with `semantic` and `index`
+	pub fn find(&self, semantic: &Semantic, index: u32) -> Option<&VertexAttribute> {
+		self.attributes.iter().find(|a| a.semantic == *semantic && a.index == index)
+	}
+
+	/// Checks that every attribute has one to four components and fits within the stride
+	pub fn validate(&self) -> Result<(), LayoutError> {
+		for (i, attribute) in self.attributes.iter().enumerate() {
+			if attribute.components == 0 || attribute.components > 4 {
+				return Err(LayoutError::ComponentCount { attribute: i, components: attribute.components });
+			}
+			if attribute.offset + attribute.size() > self.stride {
+				return Err(LayoutError::OutsideStride { attribute: i });
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Decodes `count` vertices from the start of `bytes` into one stream per attribute
+	pub fn decode(&self, bytes: &[u8], count: usize) -> Result<Vec<Stream>, LayoutError> {
+		self.validate()?;
+
+		// The last vertex only needs to reach the end of its attributes, not the full stride
+		let tail = self.attributes.iter().map(|a| a.offset + a.size()).max().unwrap_or(0);
+		let needed = if count == 0 { 0 } else { (count - 1) * self.stride + tail };
+
+		if bytes.len() < needed {
+			return Err(LayoutError::BufferTooShort { needed: needed, len: bytes.len() });
+		}
+
+		let streams = self.attributes.iter()
+			.map(|a| {
+				let size = a.format.size();
+				let mut stream = Stream::new(a.semantic.clone(), a.index, a.components);
+				stream.values.reserve(count * a.components);
+
+				for v in 0..count {
+					let start = v * self.stride + a.offset;
+					let data = &bytes[start..start + a.size()];
+					stream.values.extend(data.chunks(size).map(|c| a.format.decode(c, self.byte_order)));
+				}
+				stream
+			})
+			.collect();
+
+		Ok(streams)
+	}
+
+	/// Encodes streams into an interleaved buffer, matching each attribute to a stream by semantic
+	/// and index
+	///
+	/// Streams the layout has no attribute for are ignored and bytes no attribute covers are zero.
+	pub fn encode(&self, streams: &[Stream]) -> Result<Vec<u8>, LayoutError> {
+		self.validate()?;
+
+		let mut matched = Vec::with_capacity(self.attributes.len());
+
+		for a in self.attributes.iter() {
+			let stream = streams.iter()
+				.find(|s| s.semantic == a.semantic && s.index == a.index)
+				.ok_or_else(|| LayoutError::MissingStream { semantic: a.semantic.clone(), index: a.index })?;
+			matched.push(stream);
+		}
+
+		let count = matched.first().map_or(0, |s| s.len());
+
+		if let Some(s) = matched.iter().find(|s| s.len() != count) {
+			return Err(LayoutError::StreamLength { semantic: s.semantic.clone(), index: s.index });
+		}
+
+		let mut bytes = vec![0; count * self.stride];
+
+		for (a, stream) in self.attributes.iter().zip(matched) {
+			let size = a.format.size();
+
+			for v in 0..count {
+				let values = stream.get(v);
+				let start = v * self.stride + a.offset;
+
+				for (c, data) in bytes[start..start + a.size()].chunks_mut(size).enumerate() {
+					a.format.encode(values.get(c).cloned().unwrap_or(0.0), data, self.byte_order);
+				}
+			}
+		}
+
+		Ok(bytes)
+	}
+
+	/// Decodes `count` vertices straight into core vertices
+	///
+	/// See [`vertices_from_streams`] for how attributes map onto vertex fields.
+	pub fn decode_vertices(&self, bytes: &[u8], count: usize) -> Result<Vec<Vertex<f32, f32>>, LayoutError> {
+		self.decode(bytes, count).map(|streams| vertices_from_streams(&streams, count))
+	}
+
+	/// Encodes core vertices into an interleaved buffer
+	pub fn encode_vertices(&self, vertices: &[Vertex<f32, f32>]) -> Result<Vec<u8>, LayoutError> {
+		self.encode(&self.streams_from_vertices(vertices))
+	}
+
+	/// Builds one stream per attribute of the layout from the fields of core vertices
+	///
+	/// Attributes that have no vertex field, such as [`Semantic::Other`], get zeroed streams.
+	/// Influences are written heaviest first, so the lightest are dropped if the layout has too
+	/// few joint slots.
+	pub fn streams_from_vertices(&self, vertices: &[Vertex<f32, f32>]) -> Vec<Stream> {
+		self.attributes.iter()
+			.map(|a| {
+				let mut stream = Stream::new(a.semantic.clone(), a.index, a.components);
+				let first_slot = self.first_skin_slot(a);
+
+				for v in vertices.iter() {
+					let values: Vec<f32> = match (&a.semantic, a.index) {
+						(Semantic::Position, _) => (v.position.as_ref() as &[f32; 3]).to_vec(),
+						(Semantic::Normal, i) if i < 2 => (v.normals[i as usize].as_ref() as &[f32; 3]).to_vec(),
+						(Semantic::Tangent, 0) => (v.tangent.as_ref() as &[f32; 4]).to_vec(),
+						(Semantic::TexCoord, i) => (v.uv(i as usize).as_ref() as &[f32; 2]).to_vec(),
+						(Semantic::Color, i) => v.colors.get(i as usize)
+							.map_or_else(Vec::new, |c| vec![c.red, c.green, c.blue, c.alpha]),
+						(Semantic::Joints, _) | (Semantic::Weights, _) => {
+							let mut weights = v.weights.clone();
+							weights.sort();
+							weights.influences.iter()
+								.skip(first_slot)
+								.take(a.components)
+								.map(|i| if a.semantic == Semantic::Joints { i.joint as f32 } else { i.weight })
+								.collect()
+						},
+						_ => Vec::new(),
+					};
+					stream.push(&values);
+				}
+				stream
+			})
+			.collect()
+	}
+
+	/// Returns how many joint or weight slots come before `attribute` in lower indexed attributes
+	/// of the same semantic
+	fn first_skin_slot(&self, attribute: &VertexAttribute) -> usize {
+		self.attributes.iter()
+			.filter(|a| a.semantic == attribute.semantic && a.index < attribute.index)
+			.map(|a| a.components)
+			.sum()
+	}
+}
+
+/// Builds `count` core vertices from decoded streams
+///
+/// `Position`, `Normal` 0 and 1 and `Tangent` 0 fill the matching vertex fields, and `TexCoord`
+/// and `Color` streams fill the set with their index. Sets skipped by the indices are zero for
+/// texture coordinates and white for colors. `Joints` and `Weights` streams pair up by index into
+/// skin weights. Other streams have no place in the core vertex and are ignored. Missing color
+/// components default to one, and a three component tangent gets a `w` of one.
+pub fn vertices_from_streams(streams: &[Stream], count: usize) -> Vec<Vertex<f32, f32>> {
+	let find = |semantic: Semantic, index: u32| {
+		streams.iter().find(|s| s.semantic == semantic && s.index == index && s.len() >= count)
+	};
+	let position = find(Semantic::Position, 0);
+	let normals = [find(Semantic::Normal, 0), find(Semantic::Normal, 1)];
+	let tangent = find(Semantic::Tangent, 0);
+	let sets = |semantic: Semantic| {
+		let count = streams.iter().filter(|s| s.semantic == semantic).map(|s| s.index as usize + 1).max().unwrap_or(0);
+		(0..count as u32).map(|i| find(semantic.clone(), i)).collect::<Vec<_>>()
+	};
+	let uvs = sets(Semantic::TexCoord);
+	let colors = sets(Semantic::Color);
+
+	let mut skins: Vec<(&Stream, &Stream)> = streams.iter()
+		.filter(|s| s.semantic == Semantic::Joints && s.len() >= count)
+		.filter_map(|j| find(Semantic::Weights, j.index).map(|w| (j, w)))
+		.collect();
+	skins.sort_by_key(|(j, _)| j.index);
+
+	let zero = Vector4::zero();
+	let white = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
+	(0..count)
+		.map(|v| {
+			let mut weights = SkinWeights::default();
+
+			for (joints, weight) in skins.iter() {
+				for (&j, &w) in joints.get(v).iter().zip(weight.get(v)) {
+					weights.add(j.max(0.0) as usize, w);
+				}
+			}
+
+			Vertex {
+				position: position.map_or(Vector3::zero(), |s| s.vector4(v, zero).truncate()),
+				normals: [
+					normals[0].map_or(Vector3::zero(), |s| s.vector4(v, zero).truncate()),
+					normals[1].map_or(Vector3::zero(), |s| s.vector4(v, zero).truncate()),
+				],
+				tangent: tangent.map_or(zero, |s| s.vector4(v, Vector4::new(0.0, 0.0, 0.0, 1.0))),
+				uvs: uvs.iter().map(|s| s.map_or(Vector2::zero(), |s| s.vector4(v, zero).truncate().truncate())).collect(),
+				colors: colors.iter()
+					.map(|s| {
+						let c = s.map_or(white, |s| s.vector4(v, white));
+						ColorF {
+							red: c.x,
+							green: c.y,
+							blue: c.z,
+							alpha: c.w,
+						}
+					})
+					.collect(),
+				weights: weights,
+			}
+		})
+		.collect()
+}
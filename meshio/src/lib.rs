@@ -1,11 +1,54 @@
-use std::collections::HashMap;
+#![allow(unused_doc_comments)]
+#![allow(clippy::redundant_field_names)]
 
-/// Languages primarily used as keys for [`LocalizedStringMap`]
-#[derive(Clone,Debug,Default,PartialEq,Eq)]
-pub enum Language {
-	English,
-	Japanese
-}
+pub mod animation;
+pub mod codec;
+pub mod physics;
+pub mod process;
 
-/// Type alias for a hash map that maps strings by [`Language`]
-pub type LocalizedStringMap = HashMap<Language, String>;
+mod bounds;
+mod bvh;
+mod camera;
+mod color;
+mod coordinates;
+mod error;
+mod format;
+mod language;
+mod layout;
+mod light;
+mod material;
+mod morph;
+mod nom_ext;
+mod normals;
+mod object;
+mod scene;
+mod skeleton;
+mod skin;
+mod transform;
+mod triangulate;
+mod validation;
+mod vertex;
+mod write_ext;
+
+pub use bounds::*;
+pub use bvh::*;
+pub use camera::*;
+pub use color::*;
+pub use coordinates::*;
+pub use error::*;
+pub use format::*;
+pub use language::*;
+pub use layout::*;
+pub use light::*;
+pub use material::*;
+pub use morph::*;
+pub use nom_ext::*;
+pub use object::*;
+pub use scene::*;
+pub use skeleton::*;
+pub use skin::*;
+pub use transform::*;
+pub use triangulate::*;
+pub use validation::*;
+pub use vertex::*;
+pub use write_ext::*;
@@ -0,0 +1,78 @@
+use cgmath::BaseFloat;
+
+use std::ops::Range;
+
+use crate::{
+	ColorF,
+	LocalizedStringMap
+};
+
+/// How a [`Light`] emits, relative to the scene node that places it
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum LightKind<T> {
+	/// Emits in all directions from the node's origin
+	Point,
+	/// Emits a cone along the node's -Z axis, with half angles in radians
+	Spot {
+		/// Angle inside which the light has full intensity
+		inner_angle: T,
+		/// Angle outside which the light has no effect
+		outer_angle: T,
+	},
+	/// Emits parallel rays along the node's -Z axis
+	Directional,
+	/// Lights every surface evenly, regardless of position or direction
+	Ambient,
+}
+
+/// A light source placed in a [`Scene`](crate::Scene) by the nodes that reference it by index
+///
+/// No importer in this tree produces lights yet; scenes built in code can still carry them
+/// through [`Scene::convert_to`](crate::Scene::convert_to).
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Light<T> {
+	pub name: LocalizedStringMap,
+	pub kind: LightKind<T>,
+	/// Color in linear light
+	pub color: ColorF,
+	pub intensity: T,
+	/// Distances over which the intensity falls from full to zero, or `None` if it does not fall off
+	pub attenuation: Option<Range<T>>,
+}
+
+impl<T: BaseFloat> Light<T> {
+	/// Creates a white light of unit intensity without attenuation
+	pub fn new(name: LocalizedStringMap, kind: LightKind<T>) -> Light<T> {
+		Light {
+			name: name,
+			kind: kind,
+			color: ColorF {
+				red: 1.0,
+				green: 1.0,
+				blue: 1.0,
+				alpha: 1.0,
+			},
+			intensity: T::one(),
+			attenuation: None,
+		}
+	}
+
+	/// Returns the intensity at `distance` from the light, falling off linearly across the
+	/// attenuation range
+	pub fn intensity_at(&self, distance: T) -> T {
+		let range = match &self.attenuation {
+			Some(range) => range,
+			None => return self.intensity,
+		};
+
+		if distance <= range.start {
+			self.intensity
+		} else if distance >= range.end {
+			T::zero()
+		} else {
+			self.intensity * (range.end - distance) / (range.end - range.start)
+		}
+	}
+}
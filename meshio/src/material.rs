@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::{
+	ColorF,
+	LocalizedStringMap
+};
+
+/// Purposes a [`Texture`] can serve in a [`Material`]
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum TextureSlot {
+	Diffuse,
+	Normal,
+	Specular,
+	Emissive,
+	Opacity,
+	/// Sphere or cube reflection map
+	Environment,
+	/// Toon shading ramp, as used by MikuMikuDance
+	Toon,
+}
+
+/// An image referenced by a [`Material`]
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Texture {
+	/// Path as stored in the source file, usually relative to it
+	pub path: String,
+	/// Index of the UV set used to sample the texture
+	pub uv_set: usize,
+}
+
+impl Texture {
+	/// Creates a texture sampled with the first UV set
+	pub fn new(path: &str) -> Texture {
+		Texture {
+			path: path.to_owned(),
+			uv_set: 0,
+		}
+	}
+}
+
+/// Surface appearance shared by the nodes that reference it by index
+///
+/// Colors are in linear light; importers decode sRGB colors with [`Color::to_linear`](crate::Color::to_linear).
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Material {
+	pub name: LocalizedStringMap,
+	pub diffuse: ColorF,
+	pub specular: ColorF,
+	pub ambient: ColorF,
+	pub emissive: ColorF,
+	/// Opacity from 0 (transparent) to 1 (opaque)
+	pub opacity: f32,
+	/// Specular exponent
+	pub shininess: f32,
+	pub textures: HashMap<TextureSlot, Texture>,
+}
+
+impl Default for Material {
+	fn default() -> Material {
+		let black = ColorF {
+			red: 0.0,
+			green: 0.0,
+			blue: 0.0,
+			alpha: 1.0,
+		};
+
+		Material {
+			name: LocalizedStringMap::new(),
+			diffuse: ColorF {
+				red: 1.0,
+				green: 1.0,
+				blue: 1.0,
+				alpha: 1.0,
+			},
+			specular: black.clone(),
+			ambient: black.clone(),
+			emissive: black,
+			opacity: 1.0,
+			shininess: 0.0,
+			textures: HashMap::new(),
+		}
+	}
+}
+
+impl Material {
+	/// Returns the texture bound to `slot`
+	pub fn texture(&self, slot: TextureSlot) -> Option<&Texture> {
+		self.textures.get(&slot)
+	}
+}
@@ -0,0 +1,101 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Vector2,
+	Vector3
+};
+
+use crate::{
+	LocalizedStringMap,
+	LocalizedStringMapExt,
+	Node,
+	Object
+};
+
+/// Groups morphs by the part of a face they affect, as MikuMikuDance's morph panel does
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum MorphCategory {
+	Eyebrow,
+	Eye,
+	Mouth,
+	Other,
+}
+
+impl Default for MorphCategory {
+	fn default() -> MorphCategory {
+		MorphCategory::Other
+	}
+}
+
+/// Displacement of a single vertex at full morph weight
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct MorphOffset<P> {
+	/// Index of the node in [`Object::nodes`]
+	pub node: usize,
+	/// Index of the vertex in the node
+	pub vertex: usize,
+	pub position: Vector3<P>,
+	pub normal: Vector3<P>,
+	/// Offset of the texture coordinate set chosen by [`Morph::uv_set`]
+	pub uv: Vector2<P>,
+}
+
+/// A blend shape that sparsely displaces vertices across an object's nodes
+#[derive(Clone,Debug,Default,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Morph<P> {
+	pub name: LocalizedStringMap,
+	pub category: MorphCategory,
+	/// Index of the texture coordinate set that the offsets' `uv` displaces
+	pub uv_set: usize,
+	pub offsets: Vec<MorphOffset<P>>,
+}
+
+impl<P: BaseFloat, C: Clone> Object<P, C> {
+	/// Returns the index of the first morph with `name` in any language
+	pub fn find_morph(&self, name: &str) -> Option<usize> {
+		self.morphs.iter().position(|m| m.name.matches(name))
+	}
+
+	/// Returns copies of the nodes deformed by morphs, given as `(morph index, weight)` pairs
+	///
+	/// Offsets are scaled by their weight and summed, so weights may be negative or exceed one.
+	/// Displaced normals are renormalized. Invalid morph, node, vertex or UV set indices are
+	/// ignored.
+	pub fn morphed(&self, weights: &[(usize, P)]) -> Vec<Node<P, C>> {
+		let mut nodes = self.nodes.clone();
+		let mut touched = vec![Vec::new(); nodes.len()];
+
+		for &(index, weight) in weights {
+			let morph = match self.morphs.get(index) {
+				Some(m) => m,
+				None => continue,
+			};
+
+			for offset in morph.offsets.iter() {
+				if let Some(vertex) = nodes.get_mut(offset.node).and_then(|n| n.vertices.get_mut(offset.vertex)) {
+					vertex.position += offset.position * weight;
+					vertex.normals[0] += offset.normal * weight;
+					if let Some(uv) = vertex.uvs.get_mut(morph.uv_set) {
+						*uv += offset.uv * weight;
+					}
+					touched[offset.node].push(offset.vertex);
+				}
+			}
+		}
+
+		for (node, vertices) in nodes.iter_mut().zip(touched) {
+			for i in vertices {
+				let normal = &mut node.vertices[i].normals[0];
+
+				if normal.magnitude2() > P::zero() {
+					*normal = normal.normalize();
+				}
+			}
+		}
+
+		nodes
+	}
+}
@@ -1,20 +1,25 @@
 use cgmath::{
 	Matrix4,
-	Quaternion,
 	Vector2,
 	Vector3,
 	Vector4
 };
 
 use nom::{
+	call,
 	character::complete::digit1,
+	count,
+	do_parse,
 	flat_map,
+	length_data,
+	map_res,
 	named,
 	number::complete::{
 		be_f32,
 		be_f64,
 		le_f32,
-		le_f64
+		le_f64,
+		le_u32
 	},
 	opt,
 	pair,
@@ -24,6 +29,8 @@ use nom::{
 	take_until
 };
 
+use std::str;
+
 /// Parses a three-dimensional double precision vector in big endian order from binary input
 named!(pub be_v3d<Vector3<f64> >,
 	do_parse!(
@@ -48,12 +55,21 @@ named!(pub be_v3f<Vector3<f32> >,
 named!(pub le_mat4x3<Matrix4<f32> >,
 	do_parse!(
 		data: count!(le_f32, 12) >>
-		(Matrix4::new(data.0, data.1, data.2, 1.0,
-		data.3, data.4, data.5, 1.0,
-		data.6, data.7, data.8, 1.0,
-		data.9, data.10, data.11, 1.0))
+		(Matrix4::new(data[0], data[1], data[2], 0.0,
+		data[3], data[4], data[5], 0.0,
+		data[6], data[7], data[8], 0.0,
+		data[9], data[10], data[11], 1.0))
+	)
+);
+
+/// Parses a two-dimensional single precision vector in little endian order from binary input
+named!(pub le_v2f<Vector2<f32> >,
+	do_parse!(
+		x: le_f32 >>
+		y: le_f32 >>
+		(Vector2::new(x, y))
 	)
-)
+);
 
 /// Parses a three-dimensional double precision vector in little endian order from binary input
 named!(pub le_v3d<Vector3<f64> >,
@@ -75,11 +91,22 @@ named!(pub le_v3f<Vector3<f32> >,
 	)
 );
 
+/// Parses a four-dimensional single precision vector in little endian order from binary input
+named!(pub le_v4f<Vector4<f32> >,
+	do_parse!(
+		x: le_f32 >>
+		y: le_f32 >>
+		z: le_f32 >>
+		w: le_f32 >>
+		(Vector4::new(x, y, z, w))
+	)
+);
+
 /// Parses a signed 32-bit integer from text input
-named!(pub int<i32>, recognize!(pair!(opt!(tag!("-")), uint)));
+named!(pub int<i32>, flat_map!(recognize!(pair!(opt!(tag!("-")), digit1)), parse_to!(i32)));
 
 /// Parses an unsigned 32-bit integer from text input
-named!(pub uint<u32>, uint32);
+named!(pub uint<u32>, call!(uint32));
 
 /// Parses an unsigned byte from text input
 named!(pub uint8<u8>, flat_map!(digit1, parse_to!(u8)));
@@ -91,4 +118,7 @@ named!(pub uint16<u16>, flat_map!(digit1, parse_to!(u16)));
 named!(pub uint32<u32>, flat_map!(digit1, parse_to!(u32)));
 
 /// Parses a null-terminated string from binary input
-named!(pub zstr<&str>, take_until!("\0"));
+named!(pub zstr<&str>, map_res!(take_until!("\0"), str::from_utf8));
+
+/// Parses a string prefixed by its length in bytes as a little endian 32-bit integer from binary input
+named!(pub le_lstr<&str>, map_res!(length_data!(le_u32), str::from_utf8));
@@ -0,0 +1,175 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Vector3,
+	Vector4
+};
+
+use std::collections::HashMap;
+
+use crate::{
+	triangulate::newell_normal,
+	Face,
+	Node
+};
+
+/// Returns the angle between the edges of a polygon that meet at `corner`
+fn corner_angle<P: BaseFloat>(previous: Vector3<P>, corner: Vector3<P>, next: Vector3<P>) -> P {
+	let (a, b) = (previous - corner, next - corner);
+
+	if a.magnitude2() <= P::zero() || b.magnitude2() <= P::zero() {
+		P::zero()
+	} else {
+		let cos = a.normalize().dot(b.normalize());
+		cos.max(-P::one()).min(P::one()).acos()
+	}
+}
+
+/// Hashable key of a position, so that vertices split for UV or color seams still smooth together
+fn position_key<P: BaseFloat>(p: Vector3<P>) -> [u64; 3] {
+	// Adding zero turns negative zero into positive zero
+	let bits = |x: P| (x + P::zero()).to_f64().unwrap_or(0.0).to_bits();
+	[bits(p.x), bits(p.y), bits(p.z)]
+}
+
+/// Returns a unit vector perpendicular to `n`
+fn perpendicular<P: BaseFloat>(n: Vector3<P>) -> Vector3<P> {
+	let axis = if n.x.abs() < n.y.abs() { Vector3::unit_x() } else { Vector3::unit_y() };
+	(axis - n * n.dot(axis)).normalize()
+}
+
+impl<P: BaseFloat, C: Clone> Node<P, C> {
+	/// Replaces the first normal of every referenced vertex with an angle-weighted average of
+	/// the normals of the faces around it
+	///
+	/// Faces meeting at a position are smoothed together only if the angle between them is at
+	/// most `crease_angle` radians and, when `smoothing_groups` holds a bit mask per face, their
+	/// masks share a bit. A mask of zero leaves a face flat. Vertices that need different normals
+	/// for different faces are copied to the end of the vertex list, so existing indices stay
+	/// valid.
+	pub fn compute_normals(&mut self, crease_angle: Option<P>, smoothing_groups: Option<&[u32]>) {
+		let positions: Vec<Vector3<P>> = self.vertices.iter().map(|v| v.position).collect();
+		let faces: Vec<Vec<u32>> = self.faces.iter()
+			.map(Face::indices)
+			.map(|f| if f.iter().all(|&i| (i as usize) < positions.len()) { f } else { Vec::new() })
+			.collect();
+		let face_normals: Vec<Vector3<P>> = faces.iter().map(|f| {
+			let points: Vec<Vector3<P>> = f.iter().map(|&i| positions[i as usize]).collect();
+			let n = newell_normal(&points);
+			if n.magnitude2() > P::zero() { n.normalize() } else { n }
+		}).collect();
+
+		let cos_crease = crease_angle.map(|a| a.cos());
+		let group = |f: usize| smoothing_groups.map(|g| g.get(f).cloned().unwrap_or(0));
+		let smooth = |a: usize, b: usize| {
+			a == b || (group(a).map_or(true, |g| g & group(b).unwrap_or(0) != 0)
+				&& cos_crease.map_or(true, |c| face_normals[a].dot(face_normals[b]) >= c))
+		};
+
+		// Corners sharing a position, as (face, angle) pairs
+		let mut shared: HashMap<[u64; 3], Vec<(usize, P)>> = HashMap::new();
+
+		for (f, face) in faces.iter().enumerate() {
+			let n = face.len();
+			for k in 0..n {
+				let p = |k: usize| positions[face[k % n] as usize];
+				let angle = corner_angle(p(k + n - 1), p(k), p(k + 1));
+				shared.entry(position_key(p(k))).or_default().push((f, angle));
+			}
+		}
+
+		let tolerance = P::one() - P::from(1.0e-5).unwrap();
+		let mut assigned: Vec<Vec<(Vector3<P>, u32)>> = vec![Vec::new(); positions.len()];
+
+		for (f, face) in faces.iter().enumerate() {
+			let mut indices = face.clone();
+
+			for index in indices.iter_mut() {
+				let around = &shared[&position_key(positions[*index as usize])];
+				let sum = around.iter()
+					.filter(|&&(g, _)| smooth(f, g))
+					.fold(Vector3::new(P::zero(), P::zero(), P::zero()), |sum, &(g, w)| sum + face_normals[g] * w);
+				let normal = if sum.magnitude2() > P::zero() { sum.normalize() } else { face_normals[f] };
+				let slots = &mut assigned[*index as usize];
+
+				match slots.iter().find(|(n, _)| n.dot(normal) >= tolerance) {
+					Some(&(_, existing)) => *index = existing,
+					None if slots.is_empty() => {
+						self.vertices[*index as usize].normals[0] = normal;
+						slots.push((normal, *index));
+					},
+					None => {
+						let mut copy = self.vertices[*index as usize].clone();
+						copy.normals[0] = normal;
+						self.vertices.push(copy);
+						let copied = (self.vertices.len() - 1) as u32;
+						slots.push((normal, copied));
+						*index = copied;
+					},
+				}
+			}
+
+			if !indices.is_empty() {
+				self.faces[f] = Face::from_indices(indices);
+			}
+		}
+	}
+
+	/// Computes the tangent of every referenced vertex from its first normal and UV set
+	///
+	/// Follows MikkTSpace: per-triangle UV derivatives are projected onto the tangent plane,
+	/// weighted by corner angle and orthogonalized against the normal, and `w` holds the
+	/// bitangent sign so that `bitangent = w * normal.cross(tangent.xyz)`. Unlike MikkTSpace,
+	/// vertices are never split, so shared vertices across mirrored UV seams get an average.
+	pub fn compute_tangents(&mut self) {
+		let positions: Vec<Vector3<P>> = self.vertices.iter().map(|v| v.position).collect();
+		let zero = Vector3::new(P::zero(), P::zero(), P::zero());
+		let mut tangents = vec![zero; positions.len()];
+		let mut bitangents = vec![zero; positions.len()];
+
+		for face in self.faces.iter() {
+			for t in face.triangulate(&positions).unwrap_or_default() {
+				let corners = [t.x as usize, t.y as usize, t.z as usize];
+				let (v0, v1, v2) = (&self.vertices[corners[0]], &self.vertices[corners[1]], &self.vertices[corners[2]]);
+				let (e1, e2) = (v1.position - v0.position, v2.position - v0.position);
+				let (d1, d2) = (v1.uv(0) - v0.uv(0), v2.uv(0) - v0.uv(0));
+				let area = d1.x * d2.y - d2.x * d1.y;
+
+				if area == P::zero() {
+					continue;
+				}
+
+				let s = (e1 * d2.y - e2 * d1.y) / area;
+				let t = (e2 * d1.x - e1 * d2.x) / area;
+
+				for k in 0..3 {
+					let (i, previous, next) = (corners[k], corners[(k + 2) % 3], corners[(k + 1) % 3]);
+					let n = self.vertices[i].normals[0];
+					let weight = corner_angle(positions[previous], positions[i], positions[next]);
+					let project = |v: Vector3<P>| {
+						let v = v - n * n.dot(v);
+						if v.magnitude2() > P::zero() { v.normalize() } else { v }
+					};
+
+					tangents[i] += project(s) * weight;
+					bitangents[i] += project(t) * weight;
+				}
+			}
+		}
+
+		for ((vertex, t), b) in self.vertices.iter_mut().zip(tangents).zip(bitangents) {
+			let n = vertex.normals[0];
+			let t = t - n * n.dot(t);
+			let t = if t.magnitude2() > P::zero() {
+				t.normalize()
+			} else if n.magnitude2() > P::zero() {
+				perpendicular(n.normalize())
+			} else {
+				Vector3::unit_x()
+			};
+			let w = if n.cross(t).dot(b) < P::zero() { -P::one() } else { P::one() };
+
+			vertex.tangent = Vector4::new(t.x, t.y, t.z, w);
+		}
+	}
+}
@@ -1,11 +1,73 @@
+use crate::{
+	animation::Clip,
+	physics::Physics,
+	Face,
+	LocalizedStringMap,
+	Morph,
+	Skeleton,
+	Vertex
+};
+
+/// A mesh made of vertices and the faces that index into them, drawn with one material
 #[derive(Clone,Debug,Default,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Node<P, C> {
 	pub name: LocalizedStringMap,
 	pub vertices: Vec<Vertex<P, C>>,
+	pub faces: Vec<Face>,
+	/// Index into [`Scene::materials`](crate::Scene::materials)
+	pub material: Option<usize>,
+	/// Names of the texture coordinate sets by index, for formats that name them
+	///
+	/// May be shorter than [`Vertex::uvs`]; sets past its end are unnamed.
+	pub uv_names: Vec<String>,
+	/// Names of the vertex color sets by index, for formats that name them
+	pub color_names: Vec<String>,
 }
 
-#[derive(Clone,Debug,Default,PartialEq,Eq)]
+impl<P, C> Node<P, C> {
+	/// Returns the index of the texture coordinate set called `name`
+	pub fn uv_set(&self, name: &str) -> Option<usize> {
+		self.uv_names.iter().position(|n| n == name)
+	}
+
+	/// Returns the index of the vertex color set called `name`
+	pub fn color_set(&self, name: &str) -> Option<usize> {
+		self.color_names.iter().position(|n| n == name)
+	}
+
+	/// Returns the largest number of texture coordinate sets on any vertex
+	pub fn uv_set_count(&self) -> usize {
+		self.vertices.iter().map(|v| v.uvs.len()).max().unwrap_or(0)
+	}
+
+	/// Returns the largest number of vertex color sets on any vertex
+	pub fn color_set_count(&self) -> usize {
+		self.vertices.iter().map(|v| v.colors.len()).max().unwrap_or(0)
+	}
+}
+
+/// A named collection of [`Node`]s, optionally rigged to a [`Skeleton`] that its clips animate
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Object<P, C> {
 	pub name: LocalizedStringMap,
 	pub nodes: Vec<Node<P, C>>,
+	pub skeleton: Option<Skeleton<P>>,
+	pub animations: Vec<Clip<P>>,
+	pub morphs: Vec<Morph<P>>,
+	pub physics: Physics<P>,
+}
+
+impl<P, C> Default for Object<P, C> {
+	fn default() -> Object<P, C> {
+		Object {
+			name: LocalizedStringMap::new(),
+			nodes: Vec::new(),
+			skeleton: None,
+			animations: Vec::new(),
+			morphs: Vec::new(),
+			physics: Physics::default(),
+		}
+	}
 }
@@ -0,0 +1,245 @@
+//! Rigid bodies, collision shapes and the constraints that connect them
+
+use cgmath::{
+	BaseFloat,
+	Vector2,
+	Vector3
+};
+
+use crate::{
+	LocalizedStringMap,
+	LocalizedStringMapExt,
+	Transform
+};
+
+/// Collision geometry of a [`RigidBody`], centered on the body's origin
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum Shape<T> {
+	Sphere {
+		radius: T,
+	},
+	Box {
+		half_extents: Vector3<T>,
+	},
+	/// Cylinder with hemispherical caps, along the Y axis
+	Capsule {
+		radius: T,
+		/// Length of the cylindrical part between the caps
+		height: T,
+	},
+	/// Cylinder along the Y axis
+	Cylinder {
+		radius: T,
+		height: T,
+	},
+	/// Rectangle in the XY plane
+	Plane {
+		half_extents: Vector2<T>,
+	},
+	/// Convex hull of a point cloud
+	ConvexHull {
+		points: Vec<Vector3<T>>,
+	},
+}
+
+/// How a [`RigidBody`] and the joint it is bound to move each other
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum BodyMode {
+	/// The body follows its joint and pushes simulated bodies
+	Kinematic,
+	/// The body is simulated and drives its joint
+	Dynamic,
+	/// The body is simulated but only drives its joint's rotation
+	DynamicRotation,
+}
+
+/// A simulated or animated body, usually bound to a skeleton joint
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct RigidBody<T> {
+	pub name: LocalizedStringMap,
+	/// Index of the joint in the object's [`Skeleton`](crate::Skeleton) the body is bound to
+	pub joint: Option<usize>,
+	/// Transform relative to the joint's rest pose, or to the object if there is no joint
+	pub transform: Transform<T>,
+	pub shape: Shape<T>,
+	pub mode: BodyMode,
+	pub mass: T,
+	pub linear_damping: T,
+	pub angular_damping: T,
+	/// Bounciness from 0 (none) to 1 (perfectly elastic)
+	pub restitution: T,
+	pub friction: T,
+	/// Bit mask of the collision groups the body belongs to
+	pub group: u32,
+	/// Bit mask of the collision groups the body collides with
+	///
+	/// Formats that instead list the groups a body ignores, such as PMX's 16 bit non-collision
+	/// mask, are stored inverted, so bits outside the format's groups stay clear.
+	pub mask: u32,
+}
+
+impl<T: BaseFloat> RigidBody<T> {
+	/// Creates a kinematic body of unit mass that belongs to and collides with every group
+	pub fn new(name: LocalizedStringMap, shape: Shape<T>) -> RigidBody<T> {
+		RigidBody {
+			name: name,
+			joint: None,
+			transform: Transform::default(),
+			shape: shape,
+			mode: BodyMode::Kinematic,
+			mass: T::one(),
+			linear_damping: T::zero(),
+			angular_damping: T::zero(),
+			restitution: T::zero(),
+			friction: T::from(0.5).unwrap(),
+			group: !0,
+			mask: !0,
+		}
+	}
+}
+
+/// How a [`Constraint`] restricts the relative motion of its bodies
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub enum ConstraintKind {
+	/// No relative motion
+	Fixed,
+	/// Shared pivot point, with free rotation
+	Point,
+	/// Rotation around the constraint's X axis
+	Hinge,
+	/// Translation along and rotation around the constraint's X axis
+	Slider,
+	/// Swing within a cone around the constraint's X axis and twist around it
+	ConeTwist,
+	/// Each translation and rotation axis limited separately
+	SixDof,
+}
+
+/// Per-axis lower and upper bounds, where a lower bound above the upper one leaves the axis free
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Limits<T> {
+	pub lower: Vector3<T>,
+	pub upper: Vector3<T>,
+}
+
+impl<T: BaseFloat> Limits<T> {
+	/// Creates limits that lock every axis
+	pub fn locked() -> Limits<T> {
+		let zero = Vector3::new(T::zero(), T::zero(), T::zero());
+		Limits {
+			lower: zero,
+			upper: zero,
+		}
+	}
+
+	/// Creates limits that leave every axis free
+	pub fn free() -> Limits<T> {
+		Limits {
+			lower: Vector3::new(T::one(), T::one(), T::one()),
+			upper: Vector3::new(T::zero(), T::zero(), T::zero()),
+		}
+	}
+
+	/// Returns true if axis `axis` (0 to 2) cannot move
+	pub fn is_locked(&self, axis: usize) -> bool {
+		self.lower[axis] == self.upper[axis]
+	}
+
+	/// Returns true if axis `axis` (0 to 2) is unrestricted
+	pub fn is_free(&self, axis: usize) -> bool {
+		self.lower[axis] > self.upper[axis]
+	}
+}
+
+/// Per-axis spring that pulls a constrained axis back towards its rest position
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Spring<T> {
+	pub stiffness: Vector3<T>,
+	pub damping: Vector3<T>,
+}
+
+/// A connection between two rigid bodies that restricts their relative motion
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Constraint<T> {
+	pub name: LocalizedStringMap,
+	pub kind: ConstraintKind,
+	/// Indices into [`Physics::bodies`] of the connected bodies
+	pub bodies: [usize; 2],
+	/// Frame of the constraint in object space
+	pub transform: Transform<T>,
+	/// Translation limits along the frame's axes
+	pub linear: Limits<T>,
+	/// Rotation limits around the frame's axes in radians
+	pub angular: Limits<T>,
+	pub linear_spring: Option<Spring<T>>,
+	pub angular_spring: Option<Spring<T>>,
+}
+
+impl<T: BaseFloat> Constraint<T> {
+	/// Creates a constraint without springs that locks translation and frees rotation
+	pub fn new(name: LocalizedStringMap, kind: ConstraintKind, bodies: [usize; 2]) -> Constraint<T> {
+		Constraint {
+			name: name,
+			kind: kind,
+			bodies: bodies,
+			transform: Transform::default(),
+			linear: Limits::locked(),
+			angular: Limits::free(),
+			linear_spring: None,
+			angular_spring: None,
+		}
+	}
+}
+
+/// The physics setup of an [`Object`](crate::Object)
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Physics<T> {
+	pub bodies: Vec<RigidBody<T>>,
+	pub constraints: Vec<Constraint<T>>,
+}
+
+impl<T> Default for Physics<T> {
+	fn default() -> Physics<T> {
+		Physics {
+			bodies: Vec::new(),
+			constraints: Vec::new(),
+		}
+	}
+}
+
+impl<T> Physics<T> {
+	/// Returns true if there are no bodies or constraints
+	pub fn is_empty(&self) -> bool {
+		self.bodies.is_empty() && self.constraints.is_empty()
+	}
+
+	/// Returns the index of the first body with `name` in any language
+	pub fn find_body(&self, name: &str) -> Option<usize> {
+		self.bodies.iter().position(|b| b.name.matches(name))
+	}
+
+	/// Returns the indices of the bodies bound to joint `joint`
+	pub fn bodies_of(&self, joint: usize) -> impl Iterator<Item = usize> + '_ {
+		self.bodies.iter()
+			.enumerate()
+			.filter(move |(_, b)| b.joint == Some(joint))
+			.map(|(i, _)| i)
+	}
+
+	/// Replaces every joint index `j` with `table[j]`, as returned by
+	/// [`Skeleton::from_unordered`](crate::Skeleton::from_unordered)
+	///
+	/// Bodies bound to joints outside the table become unbound.
+	pub fn remap(&mut self, table: &[usize]) {
+		self.bodies.iter_mut()
+			.for_each(|b| b.joint = b.joint.and_then(|j| table.get(j).cloned()));
+	}
+}
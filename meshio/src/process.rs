@@ -0,0 +1,416 @@
+//! Passes that shrink and reorder meshes without changing how they look
+
+use cgmath::{
+	BaseFloat,
+	Vector3
+};
+
+use std::collections::{
+	HashMap,
+	HashSet
+};
+
+use crate::{
+	Face,
+	Node,
+	Object,
+	Vertex
+};
+
+/// Largest per-component differences at which two vertices are considered the same
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Tolerance<P> {
+	pub position: P,
+	pub normal: P,
+	pub uv: P,
+}
+
+impl<P: BaseFloat> Tolerance<P> {
+	/// Creates a tolerance that only merges identical vertices
+	pub fn exact() -> Tolerance<P> {
+		Tolerance {
+			position: P::zero(),
+			normal: P::zero(),
+			uv: P::zero(),
+		}
+	}
+
+	/// Creates a tolerance that uses `epsilon` for every attribute
+	pub fn uniform(epsilon: P) -> Tolerance<P> {
+		Tolerance {
+			position: epsilon,
+			normal: epsilon,
+			uv: epsilon,
+		}
+	}
+}
+
+/// What a pass removed
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct Report {
+	pub vertices_before: usize,
+	pub vertices_after: usize,
+	/// Vertices merged into an equivalent vertex
+	pub welded: usize,
+	/// Vertices that no face referenced
+	pub unused: usize,
+	/// Faces left with repeated vertices after welding
+	pub degenerate_faces: usize,
+	/// Faces dropped because they referenced a vertex past the end of the list
+	pub invalid_faces: usize,
+}
+
+impl Report {
+	/// Returns the fraction of vertices removed, from 0 to 1
+	pub fn reduction(&self) -> f32 {
+		if self.vertices_before == 0 {
+			0.0
+		} else {
+			1.0 - self.vertices_after as f32 / self.vertices_before as f32
+		}
+	}
+
+	fn merge(&mut self, other: Report) {
+		self.vertices_before += other.vertices_before;
+		self.vertices_after += other.vertices_after;
+		self.welded += other.welded;
+		self.unused += other.unused;
+		self.degenerate_faces += other.degenerate_faces;
+		self.invalid_faces += other.invalid_faces;
+	}
+}
+
+/// Table mapping each old vertex index to its new index, or `None` if the vertex was removed
+pub type Remap = Vec<Option<usize>>;
+
+fn within<P: BaseFloat>(a: &[P], b: &[P], epsilon: P) -> bool {
+	a.iter().zip(b).all(|(&x, &y)| (x - y).abs() <= epsilon)
+}
+
+fn same<P: BaseFloat, C: PartialEq>(a: &Vertex<P, C>, b: &Vertex<P, C>, tolerance: &Tolerance<P>) -> bool {
+	let position: &[P; 3] = a.position.as_ref();
+
+	within(position, b.position.as_ref() as &[P; 3], tolerance.position)
+		&& a.normals.iter().zip(b.normals.iter()).all(|(m, n)| within(m.as_ref() as &[P; 3], n.as_ref() as &[P; 3], tolerance.normal))
+		&& a.uvs.len() == b.uvs.len()
+		&& a.uvs.iter().zip(b.uvs.iter()).all(|(s, t)| within(s.as_ref() as &[P; 2], t.as_ref() as &[P; 2], tolerance.uv))
+		&& a.colors == b.colors
+		&& a.weights == b.weights
+}
+
+/// Returns the grid cell holding `position` for a cell size of `size`
+fn cell<P: BaseFloat>(position: Vector3<P>, size: P) -> [i64; 3] {
+	let index = |x: P| if size > P::zero() {
+		(x / size).floor().to_i64().unwrap_or(0)
+	} else {
+		(x + P::zero()).to_f64().unwrap_or(0.0).to_bits() as i64
+	};
+	[index(position.x), index(position.y), index(position.z)]
+}
+
+/// Renumbers the vertices of every face, dropping faces with an index that has no new vertex
+///
+/// Returns the number of faces dropped.
+fn apply<P, C>(node: &mut Node<P, C>, remap: &[Option<usize>]) -> usize {
+	let before = node.faces.len();
+
+	node.faces = node.faces.iter()
+		.filter_map(|f| f.indices().iter()
+			.map(|&i| remap.get(i as usize).cloned().flatten().map(|v| v as u32))
+			.collect::<Option<Vec<u32>>>())
+		.map(Face::from_indices)
+		.collect();
+	before - node.faces.len()
+}
+
+/// Drops faces with an index past the end of the vertex list, returning how many there were
+fn drop_invalid<P, C>(node: &mut Node<P, C>) -> usize {
+	let count = node.vertices.len();
+	let before = node.faces.len();
+
+	node.faces.retain(|f| f.indices().iter().all(|&i| (i as usize) < count));
+	before - node.faces.len()
+}
+
+/// Merges vertices whose attributes differ by at most `tolerance` and whose colors and skin
+/// weights are equal, then drops faces that end up with repeated vertices
+///
+/// Merged vertices are removed from the vertex list, keeping the first of each group. Faces with
+/// an index past the end of the vertex list are dropped and counted as invalid.
+pub fn weld<P: BaseFloat, C: Clone + PartialEq>(node: &mut Node<P, C>, tolerance: &Tolerance<P>) -> (Report, Remap) {
+	weld_where(node, tolerance, |_, _| true)
+}
+
+/// Welds like [`weld`], but only merges vertices `i` and `j` if `compatible(i, j)` holds
+fn weld_where<P, C, F>(node: &mut Node<P, C>, tolerance: &Tolerance<P>, compatible: F) -> (Report, Remap)
+	where P: BaseFloat, C: Clone + PartialEq, F: Fn(usize, usize) -> bool {
+	let count = node.vertices.len();
+	let invalid = drop_invalid(node);
+	let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+	let mut target: Vec<usize> = Vec::with_capacity(count);
+	let mut kept = Vec::new();
+
+	for (i, vertex) in node.vertices.iter().enumerate() {
+		let [x, y, z] = cell(vertex.position, tolerance.position);
+		let reach = if tolerance.position > P::zero() { 1 } else { 0 };
+		let mut found = None;
+
+		'search: for dx in -reach..=reach {
+			for dy in -reach..=reach {
+				for dz in -reach..=reach {
+					if let Some(candidates) = grid.get(&[x + dx, y + dy, z + dz]) {
+						found = candidates.iter().cloned()
+							.find(|&j| same(&node.vertices[j], vertex, tolerance) && compatible(j, i));
+						if found.is_some() {
+							break 'search;
+						}
+					}
+				}
+			}
+		}
+
+		match found {
+			Some(j) => target.push(target[j]),
+			None => {
+				grid.entry([x, y, z]).or_default().push(i);
+				target.push(kept.len());
+				kept.push(i);
+			},
+		}
+	}
+
+	let remap: Remap = target.into_iter().map(Some).collect();
+	let vertices = kept.iter().map(|&i| node.vertices[i].clone()).collect();
+	let faces_before = node.faces.len();
+
+	node.vertices = vertices;
+	apply(node, &remap);
+	node.faces.retain(|f| {
+		let mut indices = f.indices();
+		let n = indices.len();
+		indices.sort_unstable();
+		indices.dedup();
+		indices.len() == n
+	});
+
+	let report = Report {
+		vertices_before: count,
+		vertices_after: node.vertices.len(),
+		welded: count - node.vertices.len(),
+		unused: 0,
+		degenerate_faces: faces_before - node.faces.len(),
+		invalid_faces: invalid,
+	};
+	(report, remap)
+}
+
+/// Removes vertices that no face references, keeping the others in order
+///
+/// Faces with an index past the end of the vertex list are dropped and counted as invalid.
+pub fn remove_unused<P, C>(node: &mut Node<P, C>) -> (Report, Remap) {
+	let count = node.vertices.len();
+	let invalid = drop_invalid(node);
+	let mut used = vec![false; count];
+
+	node.faces.iter()
+		.flat_map(Face::indices)
+		.for_each(|i| used[i as usize] = true);
+
+	let mut next = 0;
+	let remap: Remap = used.iter().map(|&u| if u { next += 1; Some(next - 1) } else { None }).collect();
+	let mut i = 0;
+
+	node.vertices.retain(|_| { i += 1; used[i - 1] });
+	apply(node, &remap);
+
+	let report = Report {
+		vertices_before: count,
+		vertices_after: node.vertices.len(),
+		unused: count - node.vertices.len(),
+		invalid_faces: invalid,
+		..Default::default()
+	};
+	(report, remap)
+}
+
+/// Size of the simulated post-transform vertex cache
+const CACHE_SIZE: usize = 32;
+
+/// Scores a vertex by its position in the cache and the number of triangles still using it, as
+/// in Tom Forsyth's linear-speed vertex cache optimization
+fn vertex_score(position: Option<usize>, remaining: usize) -> f32 {
+	if remaining == 0 {
+		return -1.0;
+	}
+
+	let cache = match position {
+		Some(p) if p < 3 => 0.75,
+		Some(p) => (1.0 - (p - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(1.5),
+		None => 0.0,
+	};
+	cache + 2.0 * (remaining as f32).powf(-0.5)
+}
+
+/// Returns the order in which to draw `triangles` so that a vertex cache reuses vertices often
+fn forsyth_order(triangles: &[[usize; 3]], vertex_count: usize) -> Vec<usize> {
+	let mut adjacent: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+	triangles.iter().enumerate().for_each(|(t, tri)| tri.iter().for_each(|&v| adjacent[v].push(t)));
+
+	let mut scores: Vec<f32> = adjacent.iter().map(|a| vertex_score(None, a.len())).collect();
+	let mut triangle_scores: Vec<f32> = triangles.iter().map(|t| t.iter().map(|&v| scores[v]).sum()).collect();
+	let mut added = vec![false; triangles.len()];
+	let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+	let mut order = Vec::with_capacity(triangles.len());
+	let mut cursor = 0;
+	let mut best = None;
+
+	while order.len() < triangles.len() {
+		let t = match best {
+			Some(t) => t,
+			None => {
+				// Nothing in the cache is usable, so start from the next triangle not yet drawn
+				while added[cursor] { cursor += 1; }
+				cursor
+			},
+		};
+
+		added[t] = true;
+		order.push(t);
+
+		for &v in triangles[t].iter() {
+			adjacent[v].retain(|&u| u != t);
+			cache.retain(|&u| u != v);
+		}
+		let evicted: Vec<usize> = triangles[t].iter().cloned().chain(cache.drain(..)).collect();
+		cache = evicted.iter().cloned().take(CACHE_SIZE).collect();
+
+		for (p, &v) in evicted.iter().enumerate() {
+			scores[v] = vertex_score(Some(p).filter(|&p| p < CACHE_SIZE), adjacent[v].len());
+		}
+
+		best = None;
+		let mut best_score = -1.0;
+
+		for &v in evicted.iter() {
+			for &u in adjacent[v].iter() {
+				triangle_scores[u] = triangles[u].iter().map(|&w| scores[w]).sum();
+				if triangle_scores[u] > best_score {
+					best_score = triangle_scores[u];
+					best = Some(u);
+				}
+			}
+		}
+	}
+
+	order
+}
+
+/// Reorders triangles for post-transform vertex cache reuse, then reorders vertices by first
+/// use for pre-transform fetch locality
+///
+/// Faces that are not triangles are kept after the triangles in their original order. Faces with
+/// an index past the end of the vertex list are dropped and counted as invalid.
+pub fn optimize_vertex_cache<P, C>(node: &mut Node<P, C>) -> (Report, Remap) {
+	let count = node.vertices.len();
+	let invalid = drop_invalid(node);
+	let (triangles, others): (Vec<Face>, Vec<Face>) = node.faces.drain(..).partition(|f| matches!(f, Face::Triangle(_)));
+	let corners: Vec<[usize; 3]> = triangles.iter().map(|f| {
+		let i = f.indices();
+		[i[0] as usize, i[1] as usize, i[2] as usize]
+	}).collect();
+
+	node.faces = forsyth_order(&corners, count).into_iter()
+		.map(|t| Face::from_indices(corners[t].iter().map(|&v| v as u32).collect()))
+		.chain(others)
+		.collect();
+
+	let mut remap: Remap = vec![None; count];
+	let mut next = 0;
+
+	for i in node.faces.iter().flat_map(Face::indices) {
+		let slot = &mut remap[i as usize];
+		if slot.is_none() {
+			*slot = Some(next);
+			next += 1;
+		}
+	}
+	for slot in remap.iter_mut().filter(|s| s.is_none()) {
+		*slot = Some(next);
+		next += 1;
+	}
+
+	let mut vertices: Vec<Option<Vertex<P, C>>> = node.vertices.drain(..).map(Some).collect();
+	let mut order: Vec<(usize, usize)> = remap.iter().enumerate().map(|(old, new)| (new.unwrap(), old)).collect();
+	order.sort_unstable();
+	node.vertices = order.into_iter().map(|(_, old)| vertices[old].take().unwrap()).collect();
+	apply(node, &remap);
+
+	let report = Report {
+		vertices_before: count,
+		vertices_after: count,
+		invalid_faces: invalid,
+		..Default::default()
+	};
+	(report, remap)
+}
+
+/// Composes two remap tables applied one after the other
+fn compose(first: &[Option<usize>], second: &[Option<usize>]) -> Remap {
+	first.iter().map(|i| i.and_then(|i| second[i])).collect()
+}
+
+/// Returns the nonzero offsets each morph applies to each vertex of node `n`, ordered by morph
+fn morph_offsets<P: BaseFloat, C>(object: &Object<P, C>, n: usize) -> Vec<Vec<(usize, [P; 8])>> {
+	let count = object.nodes[n].vertices.len();
+	let mut offsets = vec![Vec::new(); count];
+
+	for (m, morph) in object.morphs.iter().enumerate() {
+		for o in morph.offsets.iter().filter(|o| o.node == n && o.vertex < count) {
+			let values = [o.position.x, o.position.y, o.position.z, o.normal.x, o.normal.y, o.normal.z, o.uv.x, o.uv.y];
+			if values.iter().any(|v| !v.is_zero()) {
+				offsets[o.vertex].push((m, values));
+			}
+		}
+	}
+	offsets
+}
+
+/// Welds, removes unused vertices and optimizes the vertex cache of every node of `object`,
+/// remapping morph offsets to match
+///
+/// Vertices are only welded when every morph moves them by the same offsets, so welding never
+/// tears a morph. Offsets of removed vertices are dropped.
+pub fn optimize<P: BaseFloat, C: Clone + PartialEq>(object: &mut Object<P, C>, tolerance: &Tolerance<P>) -> Report {
+	let mut report = Report::default();
+
+	for n in 0..object.nodes.len() {
+		let offsets = morph_offsets(object, n);
+		let node = &mut object.nodes[n];
+		let (welded, first) = weld_where(node, tolerance, |i, j| offsets[i] == offsets[j]);
+		let (unused, second) = remove_unused(node);
+		let (cache, third) = optimize_vertex_cache(node);
+		let remap = compose(&compose(&first, &second), &third);
+
+		report.merge(Report {
+			vertices_after: unused.vertices_after,
+			unused: unused.unused,
+			invalid_faces: welded.invalid_faces + unused.invalid_faces + cache.invalid_faces,
+			..welded
+		});
+
+		for morph in object.morphs.iter_mut() {
+			let mut seen = HashSet::new();
+			let offsets = morph.offsets.drain(..)
+				.filter_map(|mut o| {
+					if o.node == n {
+						o.vertex = remap.get(o.vertex).cloned().flatten().filter(|&v| seen.insert(v))?;
+					}
+					Some(o)
+				})
+				.collect();
+			morph.offsets = offsets;
+		}
+	}
+
+	report
+}
@@ -1,5 +1,196 @@
-#[derive(Clone,Debug,Default,PartialEq,Eq)]
+use cgmath::{
+	BaseFloat,
+	Matrix4,
+	SquareMatrix
+};
+
+use std::{
+	collections::HashMap,
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	}
+};
+
+use crate::{
+	Camera,
+	CoordinateSystem,
+	Light,
+	LocalizedStringMap,
+	Material,
+	Object,
+	Transform
+};
+
+/// A positioned element of a [`Scene`]'s hierarchy
+///
+/// Several nodes may reference the same object to instance it. A node that references nothing
+/// still marks a position, such as an attachment point.
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct SceneNode<T> {
+	pub name: LocalizedStringMap,
+	/// Index of the parent node, or `None` for a root
+	pub parent: Option<usize>,
+	/// Joint of the parent node's object that this node follows instead of the parent itself
+	pub joint: Option<usize>,
+	/// Transform relative to the parent
+	pub transform: Transform<T>,
+	/// Index into [`Scene::objects`] drawn at this node
+	pub object: Option<usize>,
+	/// Index into [`Scene::lights`] emitting from this node
+	pub light: Option<usize>,
+	/// Index into [`Scene::cameras`] viewing from this node
+	pub camera: Option<usize>,
+}
+
+impl<T: BaseFloat> SceneNode<T> {
+	/// Creates a root node with an identity transform
+	pub fn new(name: LocalizedStringMap) -> SceneNode<T> {
+		SceneNode {
+			name: name,
+			parent: None,
+			joint: None,
+			transform: Transform::default(),
+			object: None,
+			light: None,
+			camera: None,
+		}
+	}
+}
+
+/// Problems that prevent scene nodes from forming a hierarchy
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum NodeHierarchyError {
+	/// A node names a parent index past the end of the node list
+	ParentOutOfRange {
+		node: usize,
+		parent: usize,
+	},
+	/// Following the parents of a node leads back to itself
+	Cycle {
+		node: usize,
+	},
+}
+
+impl Display for NodeHierarchyError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			NodeHierarchyError::ParentOutOfRange { node, parent } =>
+				write!(f, "node {} has out of range parent {}", node, parent),
+			NodeHierarchyError::Cycle { node } => write!(f, "node {} is its own ancestor", node),
+		}
+	}
+}
+
+impl error::Error for NodeHierarchyError {}
+
+/// A named collection of [`Object`]s placed by a node hierarchy, the unit exchanged by importers
+/// and exporters
+///
+/// Objects that no node references are treated as placed at the origin.
+#[derive(Clone,Debug,Default,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Scene<P, C> {
 	pub name: LocalizedStringMap,
-	pub objects: Vec<Object>,
+	pub objects: Vec<Object<P, C>>,
+	pub materials: Vec<Material>,
+	pub nodes: Vec<SceneNode<P>>,
+	pub lights: Vec<Light<P>>,
+	pub cameras: Vec<Camera<P>>,
+	/// Axes and unit the scene's data is expressed in, see [`Scene::convert_to`]
+	pub coordinate_system: CoordinateSystem,
+}
+
+/// Type alias for a [`Scene`] of single precision positions and colors
+pub type SceneF = Scene<f32, f32>;
+
+impl<P: BaseFloat, C> Scene<P, C> {
+	/// Adds a node and returns its index
+	pub fn add_node(&mut self, node: SceneNode<P>) -> usize {
+		self.nodes.push(node);
+		self.nodes.len() - 1
+	}
+
+	/// Returns the indices of nodes without a parent
+	pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+		self.nodes.iter()
+			.enumerate()
+			.filter(|(_, n)| n.parent.is_none())
+			.map(|(i, _)| i)
+	}
+
+	/// Returns the indices of the direct children of node `index`
+	pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+		self.nodes.iter()
+			.enumerate()
+			.filter(move |(_, n)| n.parent == Some(index))
+			.map(|(i, _)| i)
+	}
+
+	/// Returns the indices of the nodes that instance object `index`
+	pub fn instances(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+		self.nodes.iter()
+			.enumerate()
+			.filter(move |(_, n)| n.object == Some(index))
+			.map(|(i, _)| i)
+	}
+
+	/// Computes the world transform of every node
+	///
+	/// Nodes may be listed in any order. Nodes following a joint use its rest pose; an invalid
+	/// joint or object index is treated as no joint.
+	pub fn world_matrices(&self) -> Result<Vec<Matrix4<P>>, NodeHierarchyError> {
+		let count = self.nodes.len();
+		let mut world: Vec<Option<Matrix4<P>>> = vec![None; count];
+		// Rest poses of the skeletons that nodes follow, computed once per object
+		let mut poses: HashMap<usize, Vec<Matrix4<P>>> = HashMap::new();
+		// The walk that last passed each node, to spot a walk coming back to a node on a cycle
+		let mut walked = vec![None; count];
+
+		for start in 0..count {
+			// Walk up to the nearest evaluated ancestor, then resolve back down
+			let mut chain = Vec::new();
+			let mut current = Some(start);
+
+			while let Some(i) = current {
+				if world[i].is_some() {
+					break;
+				}
+				if walked[i] == Some(start) {
+					return Err(NodeHierarchyError::Cycle { node: i });
+				}
+
+				walked[i] = Some(start);
+				chain.push(i);
+				current = match self.nodes[i].parent {
+					Some(p) if p >= count => return Err(NodeHierarchyError::ParentOutOfRange { node: i, parent: p }),
+					parent => parent,
+				};
+			}
+
+			for &i in chain.iter().rev() {
+				let node = &self.nodes[i];
+				let parent = match node.parent {
+					Some(p) => world[p].unwrap() * self.joint_matrix(&mut poses, p, node.joint),
+					None => Matrix4::identity(),
+				};
+				world[i] = Some(parent * node.transform.matrix());
+			}
+		}
+
+		Ok(world.into_iter().map(Option::unwrap).collect())
+	}
+
+	fn joint_matrix(&self, poses: &mut HashMap<usize, Vec<Matrix4<P>>>, parent: usize, joint: Option<usize>) -> Matrix4<P> {
+		joint
+			.and_then(|j| {
+				let o = self.nodes[parent].object?;
+				let skeleton = self.objects.get(o)?.skeleton.as_ref()?;
+				poses.entry(o).or_insert_with(|| skeleton.world_matrices()).get(j).cloned()
+			})
+			.unwrap_or_else(Matrix4::identity)
+	}
 }
@@ -0,0 +1,306 @@
+use cgmath::{
+	BaseFloat,
+	Matrix4,
+	Quaternion,
+	SquareMatrix,
+	Vector3
+};
+
+use std::{
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	}
+};
+
+use crate::{
+	LocalizedStringMap,
+	LocalizedStringMapExt,
+	Transform
+};
+
+/// A single joint (bone) of a [`Skeleton`] in its rest pose
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Joint<T> {
+	pub name: LocalizedStringMap,
+	/// Index of the parent joint, or `None` for a root
+	pub parent: Option<usize>,
+	/// Rest translation relative to the parent
+	pub translation: Vector3<T>,
+	/// Rest rotation relative to the parent
+	pub rotation: Quaternion<T>,
+	/// Rest scale relative to the parent
+	pub scale: Vector3<T>,
+	/// Transforms model space into the joint's space at bind time
+	pub inverse_bind: Matrix4<T>,
+}
+
+impl<T: BaseFloat> Joint<T> {
+	/// Creates a root joint with an identity rest pose
+	pub fn new(name: LocalizedStringMap) -> Joint<T> {
+		let rest = Transform::default();
+		Joint {
+			name: name,
+			parent: None,
+			translation: rest.translation,
+			rotation: rest.rotation,
+			scale: rest.scale,
+			inverse_bind: Matrix4::identity(),
+		}
+	}
+
+	/// Returns the rest pose relative to the parent joint
+	pub fn rest(&self) -> Transform<T> {
+		Transform {
+			translation: self.translation,
+			rotation: self.rotation,
+			scale: self.scale,
+		}
+	}
+
+	/// Returns the rest transform relative to the parent joint
+	pub fn local_matrix(&self) -> Matrix4<T> {
+		self.rest().matrix()
+	}
+}
+
+/// Problems that prevent joints from forming a valid [`Skeleton`]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum HierarchyError {
+	/// A joint names a parent index past the end of the joint list
+	ParentOutOfRange {
+		joint: usize,
+		parent: usize,
+	},
+	/// A joint is listed before its parent
+	ParentAfterChild {
+		joint: usize,
+		parent: usize,
+	},
+	/// Following the parents of a joint leads back to itself
+	Cycle {
+		joint: usize,
+	},
+}
+
+impl Display for HierarchyError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			HierarchyError::ParentOutOfRange { joint, parent } =>
+				write!(f, "joint {} has out of range parent {}", joint, parent),
+			HierarchyError::ParentAfterChild { joint, parent } =>
+				write!(f, "joint {} precedes its parent {}", joint, parent),
+			HierarchyError::Cycle { joint } => write!(f, "joint {} is its own ancestor", joint),
+		}
+	}
+}
+
+impl error::Error for HierarchyError {}
+
+/// A joint hierarchy in which every parent precedes its children
+#[derive(Clone,Debug,PartialEq)]
+pub struct Skeleton<T> {
+	joints: Vec<Joint<T>>,
+}
+
+/// Serializes as the list of joints
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Skeleton<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.joints.serialize(serializer)
+	}
+}
+
+/// Deserializes from a list of joints, which must be ordered parents first
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Skeleton<T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Skeleton<T>, D::Error> {
+		let joints = <Vec<Joint<T>> as serde::Deserialize>::deserialize(deserializer)?;
+		Skeleton::new(joints).map_err(serde::de::Error::custom)
+	}
+}
+
+impl<T> Default for Skeleton<T> {
+	fn default() -> Skeleton<T> {
+		Skeleton { joints: Vec::new() }
+	}
+}
+
+impl<T> Skeleton<T> {
+	/// Creates a skeleton from joints that are already ordered parents first
+	pub fn new(joints: Vec<Joint<T>>) -> Result<Skeleton<T>, HierarchyError> {
+		for (i, joint) in joints.iter().enumerate() {
+			if let Some(parent) = joint.parent {
+				if parent >= joints.len() {
+					return Err(HierarchyError::ParentOutOfRange { joint: i, parent: parent });
+				}
+				if parent == i {
+					return Err(HierarchyError::Cycle { joint: i });
+				}
+				if parent > i {
+					return Err(HierarchyError::ParentAfterChild { joint: i, parent: parent });
+				}
+			}
+		}
+
+		Ok(Skeleton { joints: joints })
+	}
+}
+
+impl<T: BaseFloat> Skeleton<T> {
+	/// Creates a skeleton from joints in any order, moving parents before their children
+	///
+	/// Returns the skeleton together with a table mapping each original joint index to its new
+	/// index, which callers use to remap skin weights and animation tracks.
+	pub fn from_unordered(mut joints: Vec<Joint<T>>) -> Result<(Skeleton<T>, Vec<usize>), HierarchyError> {
+		let count = joints.len();
+
+		for (i, joint) in joints.iter().enumerate() {
+			if let Some(parent) = joint.parent {
+				if parent >= count {
+					return Err(HierarchyError::ParentOutOfRange { joint: i, parent: parent });
+				}
+			}
+		}
+
+		// Depth-first ordering that keeps siblings in their original relative order
+		let mut children = vec![Vec::new(); count];
+		let mut roots = Vec::new();
+
+		for (i, joint) in joints.iter().enumerate() {
+			match joint.parent {
+				Some(parent) => children[parent].push(i),
+				None => roots.push(i),
+			}
+		}
+
+		let mut order = Vec::with_capacity(count);
+		let mut stack: Vec<usize> = roots.into_iter().rev().collect();
+
+		while let Some(i) = stack.pop() {
+			order.push(i);
+			stack.extend(children[i].iter().rev());
+		}
+
+		if order.len() != count {
+			// Unreached joints all have unreached parents, so following the parents of any of
+			// them ends up going around a cycle
+			let mut seen = vec![false; count];
+			order.iter().for_each(|&i| seen[i] = true);
+			let mut joint = seen.iter().position(|v| !v).unwrap_or(0);
+			let mut on_path = vec![false; count];
+
+			while !on_path[joint] {
+				on_path[joint] = true;
+				joint = joints[joint].parent.unwrap_or(joint);
+			}
+			return Err(HierarchyError::Cycle { joint: joint });
+		}
+
+		let mut remap = vec![0; count];
+		order.iter().enumerate().for_each(|(new, &old)| remap[old] = new);
+
+		let mut slots: Vec<Option<Joint<T>>> = joints.drain(..).map(Some).collect();
+		let sorted = order.iter()
+			.map(|&old| {
+				let mut joint = slots[old].take().unwrap();
+				joint.parent = joint.parent.map(|p| remap[p]);
+				joint
+			})
+			.collect();
+
+		Ok((Skeleton { joints: sorted }, remap))
+	}
+
+	/// Returns the joints, parents first
+	pub fn joints(&self) -> &[Joint<T>] {
+		&self.joints
+	}
+
+	/// Returns the joint at `index`
+	pub fn joint(&self, index: usize) -> Option<&Joint<T>> {
+		self.joints.get(index)
+	}
+
+	/// Returns a mutable reference to the joint at `index`
+	///
+	/// The parent index is not revalidated, so it should be left unchanged.
+	pub fn joint_mut(&mut self, index: usize) -> Option<&mut Joint<T>> {
+		self.joints.get_mut(index)
+	}
+
+	/// Returns the index of the first joint with `name` in any language
+	pub fn find(&self, name: &str) -> Option<usize> {
+		self.joints.iter().position(|j| j.name.matches(name))
+	}
+
+	/// Returns the number of joints
+	pub fn len(&self) -> usize {
+		self.joints.len()
+	}
+
+	/// Returns true if there are no joints
+	pub fn is_empty(&self) -> bool {
+		self.joints.is_empty()
+	}
+
+	/// Returns the indices of the direct children of `index`
+	pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+		self.joints.iter()
+			.enumerate()
+			.filter(move |(_, j)| j.parent == Some(index))
+			.map(|(i, _)| i)
+	}
+
+	/// Adds a joint after validating that its parent already exists, returning its index
+	pub fn push(&mut self, joint: Joint<T>) -> Result<usize, HierarchyError> {
+		let index = self.joints.len();
+
+		if let Some(parent) = joint.parent {
+			if parent >= index {
+				return Err(HierarchyError::ParentOutOfRange { joint: index, parent: parent });
+			}
+		}
+
+		self.joints.push(joint);
+		Ok(index)
+	}
+
+	/// Computes the model space rest transform of every joint
+	///
+	/// A joint whose parent was changed through [`Skeleton::joint_mut`] to one that no longer
+	/// precedes it is treated as a root.
+	pub fn world_matrices(&self) -> Vec<Matrix4<T>> {
+		let mut world: Vec<Matrix4<T>> = Vec::with_capacity(self.joints.len());
+
+		for joint in self.joints.iter() {
+			let local = joint.local_matrix();
+			let matrix = match joint.parent.and_then(|p| world.get(p)) {
+				Some(parent) => parent * local,
+				None => local,
+			};
+			world.push(matrix);
+		}
+
+		world
+	}
+
+	/// Recomputes every inverse bind matrix from the rest pose
+	pub fn compute_inverse_bind(&mut self) {
+		let world = self.world_matrices();
+
+		for (joint, matrix) in self.joints.iter_mut().zip(world) {
+			joint.inverse_bind = matrix.invert().unwrap_or_else(Matrix4::identity);
+		}
+	}
+}
+
+impl<T> Skeleton<T> {
+	/// Consumes the skeleton and returns its joints
+	pub fn into_joints(self) -> Vec<Joint<T>> {
+		self.joints
+	}
+}
@@ -0,0 +1,155 @@
+use cgmath::BaseFloat;
+
+use std::cmp::Ordering;
+
+/// The weight of a single [`Joint`](crate::Joint) on a vertex
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Influence<T> {
+	/// Index of the joint in the object's [`Skeleton`](crate::Skeleton)
+	pub joint: usize,
+	pub weight: T,
+}
+
+/// Joint influences on one vertex, with no limit on how many there are
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct SkinWeights<T> {
+	pub influences: Vec<Influence<T>>,
+}
+
+impl<T> Default for SkinWeights<T> {
+	fn default() -> SkinWeights<T> {
+		SkinWeights { influences: Vec::new() }
+	}
+}
+
+impl<T: BaseFloat> SkinWeights<T> {
+	/// Creates weights that bind a vertex entirely to one joint
+	pub fn single(joint: usize) -> SkinWeights<T> {
+		SkinWeights { influences: vec![Influence { joint: joint, weight: T::one() }] }
+	}
+
+	/// Creates weights from parallel joint and weight lists, as stored by most formats
+	///
+	/// Zero weights are skipped and repeated joints are merged.
+	pub fn from_slices(joints: &[usize], weights: &[T]) -> SkinWeights<T> {
+		let mut skin = SkinWeights::default();
+		joints.iter().zip(weights).for_each(|(&j, &w)| skin.add(j, w));
+		skin
+	}
+
+	/// Adds an influence, merging it into an existing one for the same joint
+	pub fn add(&mut self, joint: usize, weight: T) {
+		if weight == T::zero() {
+			return;
+		}
+
+		match self.influences.iter_mut().find(|i| i.joint == joint) {
+			Some(influence) => influence.weight += weight,
+			None => self.influences.push(Influence { joint: joint, weight: weight }),
+		}
+	}
+
+	/// Returns the number of influences
+	pub fn len(&self) -> usize {
+		self.influences.len()
+	}
+
+	/// Returns true if the vertex is not bound to any joint
+	pub fn is_empty(&self) -> bool {
+		self.influences.is_empty()
+	}
+
+	/// Returns the sum of all weights
+	pub fn total(&self) -> T {
+		self.influences.iter().fold(T::zero(), |sum, i| sum + i.weight)
+	}
+
+	/// Scales the weights to sum to one, leaving them untouched if they sum to zero
+	pub fn normalize(&mut self) {
+		let total = self.total();
+
+		if total > T::zero() {
+			self.influences.iter_mut().for_each(|i| i.weight /= total);
+		}
+	}
+
+	/// Orders the influences from heaviest to lightest
+	pub fn sort(&mut self) {
+		self.influences.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(Ordering::Equal));
+	}
+
+	/// Keeps only the `count` heaviest influences and renormalizes the remainder
+	///
+	/// Formats that store a fixed number of bones per vertex, such as XPS with four, use this
+	/// before export.
+	pub fn prune(&mut self, count: usize) {
+		self.sort();
+		self.influences.truncate(count);
+		self.normalize();
+	}
+
+	/// Removes influences lighter than `threshold` and renormalizes the remainder
+	pub fn remove_below(&mut self, threshold: T) {
+		self.influences.retain(|i| i.weight >= threshold);
+		self.normalize();
+	}
+
+	/// Replaces every joint index `j` with `table[j]`, as returned by
+	/// [`Skeleton::from_unordered`](crate::Skeleton::from_unordered)
+	///
+	/// Influences of joints outside the table are dropped without renormalizing, just as
+	/// [`Physics::remap`](crate::physics::Physics::remap) unbinds their bodies.
+	pub fn remap(&mut self, table: &[usize]) {
+		self.influences.retain(|i| i.joint < table.len());
+		self.influences.iter_mut().for_each(|i| i.joint = table[i.joint]);
+	}
+
+	/// Quantizes the normalized weights so that they sum to exactly `scale`
+	///
+	/// Rounding error is distributed to the weights with the largest remainders, so the result
+	/// never drifts from the total a format expects.
+	pub fn quantize(&self, scale: u32) -> Vec<u32> {
+		let total = self.total();
+
+		if self.influences.is_empty() || total <= T::zero() {
+			return vec![0; self.influences.len()];
+		}
+
+		let s = T::from(scale).unwrap();
+		let exact: Vec<T> = self.influences.iter().map(|i| i.weight / total * s).collect();
+		let mut quantized: Vec<u32> = exact.iter().map(|w| w.floor().to_u32().unwrap_or(0)).collect();
+		let assigned: u32 = quantized.iter().sum();
+
+		let mut order: Vec<usize> = (0..exact.len()).collect();
+		order.sort_by(|&a, &b| {
+			let fa = exact[a] - exact[a].floor();
+			let fb = exact[b] - exact[b].floor();
+			fb.partial_cmp(&fa).unwrap_or(Ordering::Equal)
+		});
+
+		for &i in order.iter().cycle().take(scale.saturating_sub(assigned) as usize) {
+			quantized[i] += 1;
+		}
+
+		quantized
+	}
+
+	/// Quantizes the weights to bytes summing to 255
+	pub fn quantize_u8(&self) -> Vec<u8> {
+		self.quantize(255).into_iter().map(|w| w as u8).collect()
+	}
+
+	/// Quantizes the weights to 16-bit integers summing to 65535
+	pub fn quantize_u16(&self) -> Vec<u16> {
+		self.quantize(65535).into_iter().map(|w| w as u16).collect()
+	}
+
+	/// Creates weights from integer weights that sum to `scale`
+	pub fn dequantize(joints: &[usize], weights: &[u32], scale: u32) -> SkinWeights<T> {
+		let s = T::from(scale).unwrap();
+		let weights: Vec<T> = weights.iter().map(|&w| T::from(w).unwrap() / s).collect();
+		SkinWeights::from_slices(joints, &weights)
+	}
+}
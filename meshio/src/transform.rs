@@ -0,0 +1,43 @@
+use cgmath::{
+	BaseFloat,
+	Matrix4,
+	One,
+	Quaternion,
+	Vector3
+};
+
+/// A translation, rotation and scale applied in scale, rotate, translate order
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
+pub struct Transform<T> {
+	pub translation: Vector3<T>,
+	pub rotation: Quaternion<T>,
+	pub scale: Vector3<T>,
+}
+
+impl<T: BaseFloat> Default for Transform<T> {
+	fn default() -> Transform<T> {
+		Transform {
+			translation: Vector3::new(T::zero(), T::zero(), T::zero()),
+			rotation: Quaternion::one(),
+			scale: Vector3::new(T::one(), T::one(), T::one()),
+		}
+	}
+}
+
+impl<T: BaseFloat> Transform<T> {
+	/// Creates a transform that only translates
+	pub fn from_translation(translation: Vector3<T>) -> Transform<T> {
+		Transform {
+			translation: translation,
+			..Default::default()
+		}
+	}
+
+	/// Returns the equivalent matrix
+	pub fn matrix(&self) -> Matrix4<T> {
+		Matrix4::from_translation(self.translation)
+			* Matrix4::from(self.rotation)
+			* Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+	}
+}
@@ -0,0 +1,202 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Vector2,
+	Vector3
+};
+
+use std::{
+	error,
+	fmt::{
+		self,
+		Display,
+		Formatter
+	}
+};
+
+use crate::{
+	Face,
+	Node
+};
+
+/// Reasons a polygon cannot be split into triangles
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Degenerate {
+	/// The polygon has fewer than three vertices
+	TooFewVertices,
+	/// The polygon references a vertex past the end of the vertex list
+	IndexOutOfRange(u32),
+	/// The polygon's vertices are coincident or collinear
+	ZeroArea,
+	/// The polygon crosses or folds back over itself so that its corners cannot be cut into
+	/// triangles covering it
+	NotSimple,
+}
+
+impl Display for Degenerate {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Degenerate::TooFewVertices => write!(f, "polygon has fewer than three vertices"),
+			Degenerate::IndexOutOfRange(index) => write!(f, "polygon references missing vertex {}", index),
+			Degenerate::ZeroArea => write!(f, "polygon has no area"),
+			Degenerate::NotSimple => write!(f, "polygon crosses itself"),
+		}
+	}
+}
+
+impl error::Error for Degenerate {}
+
+/// A face of a [`Node`] that was dropped during triangulation
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct DegenerateFace {
+	/// Index of the face in the node before triangulation
+	pub face: usize,
+	pub reason: Degenerate,
+}
+
+impl Face {
+	/// Splits the face into triangles with the same winding, using `positions` to pick diagonals
+	///
+	/// Convex quads are split along their shorter diagonal. Concave quads and n-gons are ear
+	/// clipped after projecting them onto their best fit plane, so non-planar polygons work too.
+	pub fn triangulate<P: BaseFloat>(&self, positions: &[Vector3<P>]) -> Result<Vec<Vector3<u32>>, Degenerate> {
+		let indices = self.indices();
+
+		if indices.len() < 3 {
+			return Err(Degenerate::TooFewVertices);
+		}
+		if let Some(&index) = indices.iter().find(|&&i| i as usize >= positions.len()) {
+			return Err(Degenerate::IndexOutOfRange(index));
+		}
+
+		let points: Vec<Vector3<P>> = indices.iter().map(|&i| positions[i as usize]).collect();
+		let normal = newell_normal(&points);
+
+		if normal.magnitude2() <= P::zero() {
+			return Err(Degenerate::ZeroArea);
+		}
+
+		let projected = project(&points, normal.normalize());
+		let corners = if indices.len() == 4 && is_convex(&projected) {
+			split_quad(&points)
+		} else {
+			ear_clip(&projected)?
+		};
+
+		Ok(corners.into_iter()
+			.map(|[a, b, c]| Vector3::new(indices[a], indices[b], indices[c]))
+			.collect())
+	}
+}
+
+impl<P: BaseFloat, C> Node<P, C> {
+	/// Replaces every quad and n-gon with triangles, dropping and reporting the faces that
+	/// cannot be triangulated
+	///
+	/// Degenerate triangles are dropped and reported as well.
+	pub fn triangulate(&mut self) -> Vec<DegenerateFace> {
+		let positions: Vec<Vector3<P>> = self.vertices.iter().map(|v| v.position).collect();
+		let mut faces = Vec::with_capacity(self.faces.len());
+		let mut degenerate = Vec::new();
+
+		for (i, face) in self.faces.iter().enumerate() {
+			match face.triangulate(&positions) {
+				Ok(triangles) => faces.extend(triangles.into_iter().map(Face::Triangle)),
+				Err(reason) => degenerate.push(DegenerateFace { face: i, reason: reason }),
+			}
+		}
+
+		self.faces = faces;
+		degenerate
+	}
+}
+
+/// Computes a polygon normal whose length is twice its area, which stays stable for concave
+/// and slightly non-planar polygons
+pub(crate) fn newell_normal<P: BaseFloat>(points: &[Vector3<P>]) -> Vector3<P> {
+	let zero = P::zero();
+	points.iter()
+		.zip(points.iter().cycle().skip(1))
+		.fold(Vector3::new(zero, zero, zero), |n, (a, b)| n + a.cross(*b))
+}
+
+/// Projects points onto the plane through the origin with `normal`, so that a polygon wound
+/// counter-clockwise around the normal stays counter-clockwise
+fn project<P: BaseFloat>(points: &[Vector3<P>], normal: Vector3<P>) -> Vec<Vector2<P>> {
+	let axis = if normal.x.abs() < normal.y.abs() && normal.x.abs() < normal.z.abs() {
+		Vector3::unit_x()
+	} else if normal.y.abs() < normal.z.abs() {
+		Vector3::unit_y()
+	} else {
+		Vector3::unit_z()
+	};
+	let u = normal.cross(axis).normalize();
+	let v = normal.cross(u);
+
+	points.iter().map(|p| Vector2::new(p.dot(u), p.dot(v))).collect()
+}
+
+/// Returns twice the signed area of triangle `abc`, positive if counter-clockwise
+fn cross<P: BaseFloat>(a: Vector2<P>, b: Vector2<P>, c: Vector2<P>) -> P {
+	(b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn is_convex<P: BaseFloat>(points: &[Vector2<P>]) -> bool {
+	let n = points.len();
+	(0..n).all(|i| cross(points[(i + n - 1) % n], points[i], points[(i + 1) % n]) > P::zero())
+}
+
+fn split_quad<P: BaseFloat>(points: &[Vector3<P>]) -> Vec<[usize; 3]> {
+	if (points[2] - points[0]).magnitude2() <= (points[3] - points[1]).magnitude2() {
+		vec![[0, 1, 2], [0, 2, 3]]
+	} else {
+		vec![[0, 1, 3], [1, 2, 3]]
+	}
+}
+
+fn inside<P: BaseFloat>(p: Vector2<P>, a: Vector2<P>, b: Vector2<P>, c: Vector2<P>) -> bool {
+	let zero = P::zero();
+	cross(a, b, p) >= zero && cross(b, c, p) >= zero && cross(c, a, p) >= zero
+}
+
+/// Ear clips a counter-clockwise polygon, returning corner indices of the triangles
+///
+/// When no proper ear exists, as in self-intersecting polygons, the first convex corner is
+/// clipped anyway. Fails if that still leaves corners that no triangle can cover, such as a
+/// reflex or collinear remainder.
+fn ear_clip<P: BaseFloat>(points: &[Vector2<P>]) -> Result<Vec<[usize; 3]>, Degenerate> {
+	let mut remaining: Vec<usize> = (0..points.len()).collect();
+	let mut triangles = Vec::with_capacity(points.len() - 2);
+
+	while remaining.len() > 3 {
+		let n = remaining.len();
+		let corner = |i: usize| (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+		let convex = |i: usize| {
+			let (a, b, c) = corner(i);
+			cross(points[a], points[b], points[c]) > P::zero()
+		};
+		let ear = (0..n).find(|&i| {
+			let (a, b, c) = corner(i);
+			convex(i) && remaining.iter()
+				.filter(|&&j| j != a && j != b && j != c)
+				.filter(|&&j| points[j] != points[a] && points[j] != points[b] && points[j] != points[c])
+				.all(|&j| !inside(points[j], points[a], points[b], points[c]))
+		});
+
+		match ear.or_else(|| (0..n).find(|&i| convex(i))) {
+			Some(i) => {
+				let (a, b, c) = corner(i);
+				triangles.push([a, b, c]);
+				remaining.remove(i);
+			},
+			None => return Err(Degenerate::NotSimple),
+		}
+	}
+
+	let (a, b, c) = (remaining[0], remaining[1], remaining[2]);
+	if cross(points[a], points[b], points[c]) <= P::zero() {
+		return Err(Degenerate::NotSimple);
+	}
+	triangles.push([a, b, c]);
+	Ok(triangles)
+}
@@ -0,0 +1,460 @@
+use cgmath::{
+	BaseFloat,
+	InnerSpace,
+	Vector3
+};
+
+use std::{
+	fmt::{
+		self,
+		Display,
+		Formatter
+	},
+	path::{
+		Path,
+		PathBuf
+	}
+};
+
+use crate::{
+	Degenerate,
+	HierarchyError,
+	Material,
+	Node,
+	NodeHierarchyError,
+	Scene
+};
+
+/// How serious a [`Diagnostic`] is
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub enum Severity {
+	/// Unusual but harmless
+	Info,
+	/// Likely to look wrong, but safe to process
+	Warning,
+	/// Will cause panics or garbage in code that indexes or computes with the data
+	Error,
+}
+
+/// Where in a [`Scene`] a [`Diagnostic`] was found
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Location {
+	Scene,
+	SceneNode(usize),
+	Material(usize),
+	Object(usize),
+	Node {
+		object: usize,
+		node: usize,
+	},
+	Joint {
+		object: usize,
+		joint: usize,
+	},
+	Clip {
+		object: usize,
+		clip: usize,
+	},
+	Morph {
+		object: usize,
+		morph: usize,
+	},
+	RigidBody {
+		object: usize,
+		body: usize,
+	},
+	Constraint {
+		object: usize,
+		constraint: usize,
+	},
+}
+
+/// Vertex attributes checked for non-finite values
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Attribute {
+	Position,
+	Normal,
+	Tangent,
+	Uv,
+	Weight,
+}
+
+/// Problems [`validate`] looks for
+///
+/// Problems that usually affect many vertices or faces of a node are reported once per node
+/// with a count and the first offending index.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Issue {
+	/// Faces reference vertices past the end of the node's vertex list
+	FaceIndexOutOfRange {
+		count: usize,
+		first_face: usize,
+	},
+	/// Faces have fewer than three vertices or no area
+	DegenerateFaces {
+		count: usize,
+		first_face: usize,
+		reason: Degenerate,
+	},
+	/// Vertices have NaN or infinite values
+	NonFinite {
+		attribute: Attribute,
+		count: usize,
+		first_vertex: usize,
+	},
+	/// Vertices have a first normal whose length is not one
+	NonUnitNormals {
+		count: usize,
+		first_vertex: usize,
+	},
+	/// Vertices have skin weights that do not sum to one
+	UnnormalizedWeights {
+		count: usize,
+		first_vertex: usize,
+	},
+	/// Vertices are weighted to joints the object's skeleton does not have
+	WeightJointOutOfRange {
+		count: usize,
+		first_vertex: usize,
+	},
+	/// Vertices have a different number of UV or color sets than the first vertex of their node
+	MismatchedSets {
+		count: usize,
+		first_vertex: usize,
+	},
+	/// Vertices lack a UV set that a texture of the node's material samples
+	MissingUvSet {
+		uv_set: usize,
+		count: usize,
+		first_vertex: usize,
+	},
+	/// A node has no vertices or no faces
+	EmptyNode,
+	/// An index into another list is past its end
+	IndexOutOfRange {
+		/// Name of the field holding the index
+		field: &'static str,
+		index: usize,
+		len: usize,
+	},
+	/// Joints do not form a hierarchy
+	Hierarchy(HierarchyError),
+	/// Scene nodes do not form a hierarchy
+	NodeHierarchy(NodeHierarchyError),
+	/// A texture has an empty path
+	EmptyTexturePath,
+	/// A texture file does not exist under the texture root
+	MissingTexture(PathBuf),
+}
+
+/// A problem found by [`validate`]
+#[derive(Clone,Debug,PartialEq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	pub location: Location,
+	pub issue: Issue,
+}
+
+/// Settings for [`validate_with`]
+#[derive(Clone,Debug,PartialEq)]
+pub struct ValidationOptions {
+	/// Directory relative texture paths are resolved against, usually that of the source file,
+	/// or `None` to skip checking that texture files exist
+	pub texture_root: Option<PathBuf>,
+	/// Largest accepted difference of normal lengths and weight totals from one
+	pub tolerance: f64,
+}
+
+impl Default for ValidationOptions {
+	fn default() -> ValidationOptions {
+		ValidationOptions {
+			texture_root: None,
+			tolerance: 1.0e-3,
+		}
+	}
+}
+
+impl Display for Location {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Location::Scene => write!(f, "scene"),
+			Location::SceneNode(i) => write!(f, "scene node {}", i),
+			Location::Material(i) => write!(f, "material {}", i),
+			Location::Object(i) => write!(f, "object {}", i),
+			Location::Node { object, node } => write!(f, "object {} node {}", object, node),
+			Location::Joint { object, joint } => write!(f, "object {} joint {}", object, joint),
+			Location::Clip { object, clip } => write!(f, "object {} clip {}", object, clip),
+			Location::Morph { object, morph } => write!(f, "object {} morph {}", object, morph),
+			Location::RigidBody { object, body } => write!(f, "object {} rigid body {}", object, body),
+			Location::Constraint { object, constraint } => write!(f, "object {} constraint {}", object, constraint),
+		}
+	}
+}
+
+impl Display for Issue {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Issue::FaceIndexOutOfRange { count, first_face } =>
+				write!(f, "{} faces reference missing vertices, first face {}", count, first_face),
+			Issue::DegenerateFaces { count, first_face, reason } =>
+				write!(f, "{} degenerate faces, first face {}: {}", count, first_face, reason),
+			Issue::NonFinite { attribute, count, first_vertex } =>
+				write!(f, "{} vertices have non-finite {:?} values, first vertex {}", count, attribute, first_vertex),
+			Issue::NonUnitNormals { count, first_vertex } =>
+				write!(f, "{} vertices have normals that are not unit length, first vertex {}", count, first_vertex),
+			Issue::UnnormalizedWeights { count, first_vertex } =>
+				write!(f, "{} vertices have weights that do not sum to one, first vertex {}", count, first_vertex),
+			Issue::WeightJointOutOfRange { count, first_vertex } =>
+				write!(f, "{} vertices are weighted to missing joints, first vertex {}", count, first_vertex),
+			Issue::MismatchedSets { count, first_vertex } =>
+				write!(f, "{} vertices have a different number of UV or color sets, first vertex {}", count, first_vertex),
+			Issue::MissingUvSet { uv_set, count, first_vertex } =>
+				write!(f, "{} vertices lack UV set {} used by a texture, first vertex {}", count, uv_set, first_vertex),
+			Issue::EmptyNode => write!(f, "node has no vertices or no faces"),
+			Issue::IndexOutOfRange { field, index, len } =>
+				write!(f, "{} {} is out of range for {} elements", field, index, len),
+			Issue::Hierarchy(e) => e.fmt(f),
+			Issue::NodeHierarchy(e) => e.fmt(f),
+			Issue::EmptyTexturePath => write!(f, "texture has an empty path"),
+			Issue::MissingTexture(path) => write!(f, "texture {} does not exist", path.display()),
+		}
+	}
+}
+
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		let severity = match self.severity {
+			Severity::Info => "info",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		};
+		write!(f, "{}: {}: {}", severity, self.location, self.issue)
+	}
+}
+
+/// Counts matching items and remembers the first
+#[derive(Clone,Copy,Default)]
+struct Tally {
+	count: usize,
+	first: usize,
+}
+
+impl Tally {
+	fn add(&mut self, index: usize) {
+		if self.count == 0 {
+			self.first = index;
+		}
+		self.count += 1;
+	}
+}
+
+/// Collects diagnostics
+struct Report {
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+	fn push(&mut self, severity: Severity, location: Location, issue: Issue) {
+		self.diagnostics.push(Diagnostic {
+			severity: severity,
+			location: location,
+			issue: issue,
+		});
+	}
+
+	fn index(&mut self, location: Location, field: &'static str, index: Option<usize>, len: usize) {
+		if let Some(index) = index.filter(|&i| i >= len) {
+			self.push(Severity::Error, location, Issue::IndexOutOfRange { field: field, index: index, len: len });
+		}
+	}
+
+	fn tally(&mut self, severity: Severity, location: Location, tally: Tally, issue: impl FnOnce(usize, usize) -> Issue) {
+		if tally.count > 0 {
+			self.push(severity, location, issue(tally.count, tally.first));
+		}
+	}
+
+	fn node<P: BaseFloat, C>(&mut self, location: Location, node: &Node<P, C>, joints: usize, materials: &[Material], tolerance: P) {
+		if node.vertices.is_empty() || node.faces.is_empty() {
+			self.push(Severity::Warning, location, Issue::EmptyNode);
+		}
+		self.index(location, "material", node.material, materials.len());
+
+		let uv_sets = node.material
+			.and_then(|m| materials.get(m))
+			.and_then(|m| m.textures.values().map(|t| t.uv_set + 1).max())
+			.unwrap_or(0);
+		let sets = node.vertices.first().map_or((0, 0), |v| (v.uvs.len(), v.colors.len()));
+		let (mut mismatched, mut missing_uvs) = (Tally::default(), Tally::default());
+
+		let finite3 = |v: Vector3<P>| v.x.is_finite() && v.y.is_finite() && v.z.is_finite();
+		let mut non_finite = [Tally::default(), Tally::default(), Tally::default(), Tally::default(), Tally::default()];
+		let (mut normals, mut weights, mut weight_joints) = (Tally::default(), Tally::default(), Tally::default());
+
+		for (i, v) in node.vertices.iter().enumerate() {
+			let checks = [
+				finite3(v.position),
+				finite3(v.normals[0]) && finite3(v.normals[1]),
+				finite3(v.tangent.truncate()) && v.tangent.w.is_finite(),
+				v.uvs.iter().all(|uv| uv.x.is_finite() && uv.y.is_finite()),
+				v.weights.influences.iter().all(|w| w.weight.is_finite()),
+			];
+			for (tally, ok) in non_finite.iter_mut().zip(checks.iter()) {
+				if !ok {
+					tally.add(i);
+				}
+			}
+
+			if checks[1] && (v.normals[0].magnitude() - P::one()).abs() > tolerance {
+				normals.add(i);
+			}
+			if !v.weights.is_empty() && checks[4] && (v.weights.total() - P::one()).abs() > tolerance {
+				weights.add(i);
+			}
+			if v.weights.influences.iter().any(|w| w.joint >= joints) {
+				weight_joints.add(i);
+			}
+			if (v.uvs.len(), v.colors.len()) != sets {
+				mismatched.add(i);
+			}
+			if v.uvs.len() < uv_sets {
+				missing_uvs.add(i);
+			}
+		}
+
+		let attributes = [Attribute::Position, Attribute::Normal, Attribute::Tangent, Attribute::Uv, Attribute::Weight];
+		for (tally, attribute) in non_finite.iter().zip(attributes.iter()) {
+			self.tally(Severity::Error, location, *tally, |count, first| Issue::NonFinite {
+				attribute: *attribute,
+				count: count,
+				first_vertex: first,
+			});
+		}
+		self.tally(Severity::Warning, location, normals, |count, first| Issue::NonUnitNormals { count: count, first_vertex: first });
+		self.tally(Severity::Warning, location, weights, |count, first| Issue::UnnormalizedWeights { count: count, first_vertex: first });
+		self.tally(Severity::Error, location, weight_joints, |count, first| Issue::WeightJointOutOfRange { count: count, first_vertex: first });
+		self.tally(Severity::Warning, location, mismatched, |count, first| Issue::MismatchedSets { count: count, first_vertex: first });
+		self.tally(Severity::Warning, location, missing_uvs, |count, first| Issue::MissingUvSet {
+			uv_set: uv_sets - 1,
+			count: count,
+			first_vertex: first,
+		});
+
+		let positions: Vec<Vector3<P>> = node.vertices.iter().map(|v| v.position).collect();
+		let mut out_of_range = Tally::default();
+		let mut degenerate = Tally::default();
+		let mut reason = Degenerate::ZeroArea;
+
+		for (f, face) in node.faces.iter().enumerate() {
+			match face.triangulate(&positions) {
+				Err(Degenerate::IndexOutOfRange(_)) => out_of_range.add(f),
+				Err(r) => {
+					if degenerate.count == 0 {
+						reason = r;
+					}
+					degenerate.add(f);
+				},
+				Ok(_) => {},
+			}
+		}
+		self.tally(Severity::Error, location, out_of_range, |count, first| Issue::FaceIndexOutOfRange { count: count, first_face: first });
+		self.tally(Severity::Warning, location, degenerate, |count, first| Issue::DegenerateFaces { count: count, first_face: first, reason: reason });
+	}
+}
+
+/// Checks a scene for out-of-range indices, broken hierarchies, invalid vertex data and empty
+/// nodes, without checking texture files
+///
+/// Diagnostics are ordered by where they were found, not by severity.
+pub fn validate<P: BaseFloat, C>(scene: &Scene<P, C>) -> Vec<Diagnostic> {
+	validate_with(scene, &ValidationOptions::default())
+}
+
+/// Checks a scene like [`validate`] with custom settings
+pub fn validate_with<P: BaseFloat, C>(scene: &Scene<P, C>, options: &ValidationOptions) -> Vec<Diagnostic> {
+	let mut report = Report { diagnostics: Vec::new() };
+	let tolerance = P::from(options.tolerance).unwrap_or_else(P::epsilon);
+
+	for (i, node) in scene.nodes.iter().enumerate() {
+		let location = Location::SceneNode(i);
+		report.index(location, "object", node.object, scene.objects.len());
+		report.index(location, "light", node.light, scene.lights.len());
+		report.index(location, "camera", node.camera, scene.cameras.len());
+
+		// A root node or one whose parent draws no skinned object has no joints to follow
+		let joints = node.parent
+			.and_then(|p| scene.nodes.get(p))
+			.and_then(|p| p.object)
+			.and_then(|o| scene.objects.get(o))
+			.map_or(0, |o| o.skeleton.as_ref().map_or(0, |s| s.len()));
+		report.index(location, "joint", node.joint, joints);
+	}
+
+	if let Err(e) = scene.world_matrices() {
+		report.push(Severity::Error, Location::Scene, Issue::NodeHierarchy(e));
+	}
+
+	for (i, material) in scene.materials.iter().enumerate() {
+		for texture in material.textures.values() {
+			if texture.path.is_empty() {
+				report.push(Severity::Warning, Location::Material(i), Issue::EmptyTexturePath);
+			} else if let Some(root) = options.texture_root.as_ref() {
+				// Many formats were written on Windows
+				let path = root.join(texture.path.replace('\\', "/"));
+				if !Path::new(&path).exists() {
+					report.push(Severity::Warning, Location::Material(i), Issue::MissingTexture(path));
+				}
+			}
+		}
+	}
+
+	for (o, object) in scene.objects.iter().enumerate() {
+		let joints = object.skeleton.as_ref().map_or(0, |s| s.len());
+
+		// Parents can be edited through `Skeleton::joint_mut`, so the ordering may no longer hold
+		for (j, joint) in object.skeleton.iter().flat_map(|s| s.joints()).enumerate() {
+			let error = match joint.parent {
+				Some(p) if p >= joints => Some(HierarchyError::ParentOutOfRange { joint: j, parent: p }),
+				Some(p) if p == j => Some(HierarchyError::Cycle { joint: j }),
+				Some(p) if p > j => Some(HierarchyError::ParentAfterChild { joint: j, parent: p }),
+				_ => None,
+			};
+			if let Some(e) = error {
+				report.push(Severity::Error, Location::Joint { object: o, joint: j }, Issue::Hierarchy(e));
+			}
+		}
+
+		for (n, node) in object.nodes.iter().enumerate() {
+			report.node(Location::Node { object: o, node: n }, node, joints, &scene.materials, tolerance);
+		}
+
+		for (c, clip) in object.animations.iter().enumerate() {
+			for channel in clip.channels.iter() {
+				report.index(Location::Clip { object: o, clip: c }, "channel joint", Some(channel.joint), joints);
+			}
+		}
+
+		for (m, morph) in object.morphs.iter().enumerate() {
+			let location = Location::Morph { object: o, morph: m };
+			for offset in morph.offsets.iter() {
+				match object.nodes.get(offset.node) {
+					Some(node) => report.index(location, "offset vertex", Some(offset.vertex), node.vertices.len()),
+					None => report.index(location, "offset node", Some(offset.node), object.nodes.len()),
+				}
+			}
+		}
+
+		for (b, body) in object.physics.bodies.iter().enumerate() {
+			report.index(Location::RigidBody { object: o, body: b }, "joint", body.joint, joints);
+		}
+
+		for (c, constraint) in object.physics.constraints.iter().enumerate() {
+			let location = Location::Constraint { object: o, constraint: c };
+			for &body in constraint.bodies.iter() {
+				report.index(location, "body", Some(body), object.physics.bodies.len());
+			}
+		}
+	}
+
+	report.diagnostics
+}
@@ -1,20 +1,63 @@
 use cgmath::{
+	BaseFloat,
 	Vector2,
 	Vector3,
-	Vector4
+	Vector4,
+	Zero
 };
 
-#[derive(Clone,Debug,Default,PartialEq,Eq)]
+use crate::{
+	Color,
+	SkinWeights
+};
+
+/// A point of a mesh with its normals, tangent, texture coordinates, colors and joint weights
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub struct Vertex<P, C> {
 	pub position: Vector3<P>,
-	pub normals: [Vector3<P>, 2],
-	pub uv: Vector2<P>,
-	pub color: Color<C>,
+	pub normals: [Vector3<P>; 2],
+	/// Tangent in `xyz` with the sign of the bitangent in `w`, following MikkTSpace
+	pub tangent: Vector4<P>,
+	/// Texture coordinate sets, indexed by [`Texture::uv_set`](crate::Texture::uv_set)
+	pub uvs: Vec<Vector2<P>>,
+	/// Vertex color sets, empty if the mesh has no vertex colors
+	pub colors: Vec<Color<C>>,
+	pub weights: SkinWeights<P>,
+}
+
+impl<P: BaseFloat, C> Vertex<P, C> {
+	/// Returns the texture coordinate in `set`, or zero if the vertex has no such set
+	pub fn uv(&self, set: usize) -> Vector2<P> {
+		self.uvs.get(set).cloned().unwrap_or_else(Vector2::zero)
+	}
 }
 
-#[derive(Clone,Debug,Default,PartialEq,Eq)]
+/// A polygon described by indices into a vertex list
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize,serde::Deserialize))]
 pub enum Face {
 	Triangle(Vector3<u32>),
 	Quad(Vector4<u32>),
 	Ngon(Vec<u32>),
 }
+
+impl Face {
+	/// Creates the smallest variant that holds `indices`
+	pub fn from_indices(indices: Vec<u32>) -> Face {
+		match indices.len() {
+			3 => Face::Triangle(Vector3::new(indices[0], indices[1], indices[2])),
+			4 => Face::Quad(Vector4::new(indices[0], indices[1], indices[2], indices[3])),
+			_ => Face::Ngon(indices),
+		}
+	}
+
+	/// Returns the vertex indices in winding order
+	pub fn indices(&self) -> Vec<u32> {
+		match self {
+			Face::Triangle(t) => vec![t.x, t.y, t.z],
+			Face::Quad(q) => vec![q.x, q.y, q.z, q.w],
+			Face::Ngon(n) => n.clone(),
+		}
+	}
+}
@@ -0,0 +1,258 @@
+use cgmath::{
+	Matrix4,
+	Vector2,
+	Vector3,
+	Vector4
+};
+
+use std::{
+	convert::TryFrom,
+	io::{
+		self,
+		Write
+	}
+};
+
+use crate::{
+	Bounds32,
+	Bounds64,
+	ColorB,
+	ColorF
+};
+
+/// Writes the binary layouts read by the crate's vector, matrix, [`Bounds`](crate::Bounds),
+/// [`Color`](crate::Color) and string parsers
+///
+/// Each method is named after the parser it mirrors, so `write_le_v3f` produces what `le_v3f`
+/// reads.
+pub trait WriteExt: Write {
+	/// Writes a byte
+	fn write_u8(&mut self, v: u8) -> io::Result<()> {
+		self.write_all(&[v])
+	}
+
+	/// Writes an unsigned 16-bit integer in little endian order
+	fn write_le_u16(&mut self, v: u16) -> io::Result<()> {
+		self.write_all(&v.to_le_bytes())
+	}
+
+	/// Writes an unsigned 32-bit integer in little endian order
+	fn write_le_u32(&mut self, v: u32) -> io::Result<()> {
+		self.write_all(&v.to_le_bytes())
+	}
+
+	/// Writes a signed 32-bit integer in little endian order
+	fn write_le_i32(&mut self, v: i32) -> io::Result<()> {
+		self.write_all(&v.to_le_bytes())
+	}
+
+	/// Writes a single precision float in little endian order
+	fn write_le_f32(&mut self, v: f32) -> io::Result<()> {
+		self.write_all(&v.to_bits().to_le_bytes())
+	}
+
+	/// Writes a double precision float in little endian order
+	fn write_le_f64(&mut self, v: f64) -> io::Result<()> {
+		self.write_all(&v.to_bits().to_le_bytes())
+	}
+
+	/// Writes an unsigned 16-bit integer in big endian order
+	fn write_be_u16(&mut self, v: u16) -> io::Result<()> {
+		self.write_all(&v.to_be_bytes())
+	}
+
+	/// Writes an unsigned 32-bit integer in big endian order
+	fn write_be_u32(&mut self, v: u32) -> io::Result<()> {
+		self.write_all(&v.to_be_bytes())
+	}
+
+	/// Writes a signed 32-bit integer in big endian order
+	fn write_be_i32(&mut self, v: i32) -> io::Result<()> {
+		self.write_all(&v.to_be_bytes())
+	}
+
+	/// Writes a single precision float in big endian order
+	fn write_be_f32(&mut self, v: f32) -> io::Result<()> {
+		self.write_all(&v.to_bits().to_be_bytes())
+	}
+
+	/// Writes a double precision float in big endian order
+	fn write_be_f64(&mut self, v: f64) -> io::Result<()> {
+		self.write_all(&v.to_bits().to_be_bytes())
+	}
+
+	/// Writes a two-dimensional single precision vector in little endian order
+	fn write_le_v2f(&mut self, v: Vector2<f32>) -> io::Result<()> {
+		self.write_le_f32(v.x)?;
+		self.write_le_f32(v.y)
+	}
+
+	/// Writes a three-dimensional single precision vector in little endian order
+	fn write_le_v3f(&mut self, v: Vector3<f32>) -> io::Result<()> {
+		self.write_le_f32(v.x)?;
+		self.write_le_f32(v.y)?;
+		self.write_le_f32(v.z)
+	}
+
+	/// Writes a three-dimensional double precision vector in little endian order
+	fn write_le_v3d(&mut self, v: Vector3<f64>) -> io::Result<()> {
+		self.write_le_f64(v.x)?;
+		self.write_le_f64(v.y)?;
+		self.write_le_f64(v.z)
+	}
+
+	/// Writes a four-dimensional single precision vector in little endian order
+	fn write_le_v4f(&mut self, v: Vector4<f32>) -> io::Result<()> {
+		self.write_le_f32(v.x)?;
+		self.write_le_f32(v.y)?;
+		self.write_le_f32(v.z)?;
+		self.write_le_f32(v.w)
+	}
+
+	/// Writes a three-dimensional single precision vector in big endian order
+	fn write_be_v3f(&mut self, v: Vector3<f32>) -> io::Result<()> {
+		self.write_be_f32(v.x)?;
+		self.write_be_f32(v.y)?;
+		self.write_be_f32(v.z)
+	}
+
+	/// Writes a three-dimensional double precision vector in big endian order
+	fn write_be_v3d(&mut self, v: Vector3<f64>) -> io::Result<()> {
+		self.write_be_f64(v.x)?;
+		self.write_be_f64(v.y)?;
+		self.write_be_f64(v.z)
+	}
+
+	/// Writes the upper 4x3 part of a single precision matrix in little endian order, dropping
+	/// the last row
+	fn write_le_mat4x3(&mut self, m: &Matrix4<f32>) -> io::Result<()> {
+		for column in [m.x, m.y, m.z, m.w].iter() {
+			self.write_le_v3f(column.truncate())?;
+		}
+		Ok(())
+	}
+
+	/// Writes a [`Bounds32`] in little endian with radius, writing zero if it has none
+	fn write_le_bounds32_r(&mut self, b: &Bounds32) -> io::Result<()> {
+		self.write_le_bounds32(b)?;
+		self.write_le_f32(b.radius.unwrap_or(0.0))
+	}
+
+	/// Writes a [`Bounds64`] in little endian with radius, writing zero if it has none
+	fn write_le_bounds64_r(&mut self, b: &Bounds64) -> io::Result<()> {
+		self.write_le_bounds64(b)?;
+		self.write_le_f64(b.radius.unwrap_or(0.0))
+	}
+
+	/// Writes a [`Bounds32`] in big endian with radius, writing zero if it has none
+	fn write_be_bounds32_r(&mut self, b: &Bounds32) -> io::Result<()> {
+		self.write_be_bounds32(b)?;
+		self.write_be_f32(b.radius.unwrap_or(0.0))
+	}
+
+	/// Writes a [`Bounds64`] in big endian with radius, writing zero if it has none
+	fn write_be_bounds64_r(&mut self, b: &Bounds64) -> io::Result<()> {
+		self.write_be_bounds64(b)?;
+		self.write_be_f64(b.radius.unwrap_or(0.0))
+	}
+
+	/// Writes a [`Bounds32`] in little endian with no radius
+	fn write_le_bounds32(&mut self, b: &Bounds32) -> io::Result<()> {
+		self.write_le_v3f(b.min)?;
+		self.write_le_v3f(b.max)
+	}
+
+	/// Writes a [`Bounds64`] in little endian with no radius
+	fn write_le_bounds64(&mut self, b: &Bounds64) -> io::Result<()> {
+		self.write_le_v3d(b.min)?;
+		self.write_le_v3d(b.max)
+	}
+
+	/// Writes a [`Bounds32`] in big endian with no radius
+	fn write_be_bounds32(&mut self, b: &Bounds32) -> io::Result<()> {
+		self.write_be_v3f(b.min)?;
+		self.write_be_v3f(b.max)
+	}
+
+	/// Writes a [`Bounds64`] in big endian with no radius
+	fn write_be_bounds64(&mut self, b: &Bounds64) -> io::Result<()> {
+		self.write_be_v3d(b.min)?;
+		self.write_be_v3d(b.max)
+	}
+
+	/// Writes a [`ColorB`] as little endian RGBA
+	fn write_le_rgba_b(&mut self, c: &ColorB) -> io::Result<()> {
+		self.write_all(&[c.red, c.green, c.blue, c.alpha])
+	}
+
+	/// Writes a [`ColorB`] as little endian ARGB
+	fn write_le_argb_b(&mut self, c: &ColorB) -> io::Result<()> {
+		self.write_all(&[c.alpha, c.red, c.green, c.blue])
+	}
+
+	/// Writes a [`ColorB`] as little endian BGRA
+	fn write_le_bgra_b(&mut self, c: &ColorB) -> io::Result<()> {
+		self.write_all(&[c.blue, c.green, c.red, c.alpha])
+	}
+
+	/// Writes a [`ColorF`] as little endian RGBA
+	fn write_le_rgba_f(&mut self, c: &ColorF) -> io::Result<()> {
+		self.write_le_rgb_f(c)?;
+		self.write_le_f32(c.alpha)
+	}
+
+	/// Writes a [`ColorF`] as little endian ARGB
+	fn write_le_argb_f(&mut self, c: &ColorF) -> io::Result<()> {
+		self.write_le_f32(c.alpha)?;
+		self.write_le_rgb_f(c)
+	}
+
+	/// Writes a [`ColorF`] as little endian RGB, dropping alpha
+	fn write_le_rgb_f(&mut self, c: &ColorF) -> io::Result<()> {
+		self.write_le_f32(c.red)?;
+		self.write_le_f32(c.green)?;
+		self.write_le_f32(c.blue)
+	}
+
+	/// Writes a [`ColorB`] as big endian ARGB
+	fn write_be_argb_b(&mut self, c: &ColorB) -> io::Result<()> {
+		self.write_all(&[c.alpha, c.red, c.green, c.blue])
+	}
+
+	/// Writes a [`ColorB`] as big endian BGRA
+	fn write_be_bgra_b(&mut self, c: &ColorB) -> io::Result<()> {
+		self.write_all(&[c.blue, c.green, c.red, c.alpha])
+	}
+
+	/// Writes a [`ColorF`] as big endian RGBA
+	fn write_be_rgba_f(&mut self, c: &ColorF) -> io::Result<()> {
+		self.write_be_rgb_f(c)?;
+		self.write_be_f32(c.alpha)
+	}
+
+	/// Writes a [`ColorF`] as big endian RGB, dropping alpha
+	fn write_be_rgb_f(&mut self, c: &ColorF) -> io::Result<()> {
+		self.write_be_f32(c.red)?;
+		self.write_be_f32(c.green)?;
+		self.write_be_f32(c.blue)
+	}
+
+	/// Writes a null-terminated string
+	fn write_zstr(&mut self, s: &str) -> io::Result<()> {
+		self.write_all(s.as_bytes())?;
+		self.write_u8(0)
+	}
+
+	/// Writes a string prefixed by its length in bytes as a little endian 32-bit integer
+	///
+	/// Fails with [`io::ErrorKind::InvalidInput`] without writing anything if the string is
+	/// 4 GiB or longer.
+	fn write_le_lstr(&mut self, s: &str) -> io::Result<()> {
+		let len = u32::try_from(s.len())
+			.map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "string is too long for a 32-bit length"))?;
+		self.write_le_u32(len)?;
+		self.write_all(s.as_bytes())
+	}
+}
+
+impl<W: Write + ?Sized> WriteExt for W {}
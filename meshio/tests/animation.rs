@@ -0,0 +1,109 @@
+use cgmath::{
+	Deg,
+	InnerSpace,
+	Quaternion,
+	Rotation3,
+	Vector3
+};
+
+use meshio::{
+	animation::*,
+	*
+};
+
+fn track(interpolation: Interpolation, keys: &[(f64, f64)]) -> Track<f64, f64> {
+	Track::new(interpolation, keys.iter().map(|&(t, v)| Keyframe::new(t, v)).collect())
+}
+
+fn close(a: f64, b: f64) -> bool {
+	(a - b).abs() < 1e-9
+}
+
+#[test]
+fn constant_holds_previous_key() {
+	let track = track(Interpolation::Constant, &[(0.0, 1.0), (1.0, 3.0)]);
+	assert_eq!(track.sample(0.99), Some(1.0));
+	assert_eq!(track.sample(1.0), Some(3.0));
+}
+
+#[test]
+fn linear_interpolates() {
+	let track = track(Interpolation::Linear, &[(1.0, 1.0), (0.0, 0.0), (3.0, 2.0)]);
+	assert_eq!(track.sample(-1.0), Some(0.0));
+	assert_eq!(track.sample(0.25), Some(0.25));
+	assert_eq!(track.sample(2.0), Some(1.5));
+	assert_eq!(track.sample(5.0), Some(2.0));
+	assert_eq!(Track::<f64, f64>::default().sample(0.0), None);
+}
+
+#[test]
+fn hermite_uses_rates() {
+	let flat = track(Interpolation::Hermite, &[(0.0, 1.0), (2.0, 1.0)]);
+	assert!(close(flat.sample(1.0).unwrap(), 1.0));
+
+	// A rate of one unit per second at both ends of a two second rise of two units is a line
+	let mut line = track(Interpolation::Hermite, &[(0.0, 0.0), (2.0, 2.0)]);
+	line.keys[0].out_tangent = 1.0;
+	line.keys[1].in_tangent = 1.0;
+	assert!(close(line.sample(0.5).unwrap(), 0.5));
+	assert!(close(line.sample(1.5).unwrap(), 1.5));
+}
+
+#[test]
+fn bezier_uses_control_point_offsets() {
+	let flat = track(Interpolation::Bezier, &[(0.0, 1.0), (1.0, 1.0)]);
+	assert!(close(flat.sample(0.5).unwrap(), 1.0));
+
+	// Flat tangents ease in and out symmetrically
+	let ease = track(Interpolation::Bezier, &[(0.0, 0.0), (1.0, 1.0)]);
+	assert!(close(ease.sample(0.5).unwrap(), 0.5));
+	assert!(ease.sample(0.25).unwrap() < 0.25);
+
+	// Control points at thirds of the rise are a line
+	let mut line = track(Interpolation::Bezier, &[(0.0, 0.0), (1.0, 3.0)]);
+	line.keys[0].out_tangent = 1.0;
+	line.keys[1].in_tangent = -1.0;
+	assert!(close(line.sample(0.25).unwrap(), 0.75));
+}
+
+#[test]
+fn rotations_take_the_shortest_arc() {
+	let a = Quaternion::from_angle_y(Deg(10.0));
+	let b = -Quaternion::from_angle_y(Deg(50.0));
+
+	for &interpolation in [Interpolation::Linear, Interpolation::Hermite, Interpolation::Bezier].iter() {
+		let track = Track::new(interpolation, vec![Keyframe::new(0.0, a), Keyframe::new(1.0, b)]);
+		let q = track.sample(0.5).unwrap();
+		assert!(close(q.magnitude(), 1.0));
+		assert!(close(q.dot(Quaternion::from_angle_y(Deg(30.0))).abs(), 1.0), "{:?} gave {:?}", interpolation, q);
+	}
+}
+
+#[test]
+fn clip_sample_uses_rest_pose_for_unkeyed_components() {
+	let mut root = Joint::new(LocalizedStringMap::new());
+	root.translation = Vector3::new(0.0, 1.0, 0.0);
+	root.scale = Vector3::new(2.0, 2.0, 2.0);
+	let mut child = Joint::new(LocalizedStringMap::new());
+	child.parent = Some(0);
+	child.translation = Vector3::new(0.0, 0.0, 1.0);
+	let skeleton = Skeleton::new(vec![root.clone(), child]).unwrap();
+
+	let mut channel = Channel::new(0);
+	channel.rotation = Track::new(Interpolation::Linear, vec![
+		Keyframe::new(0.0, Quaternion::from_angle_z(Deg(0.0))),
+		Keyframe::new(1.0, Quaternion::from_angle_z(Deg(90.0))),
+	]);
+	let mut clip = Clip::new(LocalizedStringMap::new(), 1.0);
+	clip.channels.push(channel);
+
+	let mut posed = root.rest();
+	posed.rotation = Quaternion::from_angle_z(Deg(90.0));
+	let pose = clip.sample(&skeleton, 1.0);
+	assert_eq!(pose[0], posed.matrix());
+	assert_eq!(pose[1], skeleton.joints()[1].local_matrix());
+	assert_eq!(clip.sample(&skeleton, 0.0)[0], root.local_matrix());
+
+	let world = clip.sample_world(&skeleton, 1.0);
+	assert_eq!(world[1], posed.matrix() * skeleton.joints()[1].local_matrix());
+}
@@ -0,0 +1,123 @@
+use cgmath::{
+	Deg,
+	Matrix4,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use meshio::*;
+
+mod common;
+
+/// A `size` by `size` grid of unit quads in the plane at height `z`
+fn grid(size: u32, z: f64) -> Node<f64, f32> {
+	let mut node = Node::default();
+
+	for y in 0..=size {
+		for x in 0..=size {
+			node.vertices.push(common::vertex(Vector3::new(f64::from(x), f64::from(y), z)));
+		}
+	}
+	for y in 0..size {
+		for x in 0..size {
+			let i = y * (size + 1) + x;
+			node.faces.push(Face::Quad(Vector4::new(i, i + 1, i + size + 2, i + size + 1)));
+		}
+	}
+	node
+}
+
+fn object() -> Object<f64, f32> {
+	Object {
+		nodes: vec![grid(8, 0.0), grid(4, 1.0)],
+		..Default::default()
+	}
+}
+
+#[test]
+fn bounds_of_points() {
+	assert_eq!(Bounds::<f64>::from_points(Vec::new()), None);
+
+	let bounds = Bounds::from_points(vec![Vector3::new(-1.0, 0.0, 2.0), Vector3::new(3.0, 2.0, 2.0)]).unwrap();
+	assert_eq!((bounds.min, bounds.max), (Vector3::new(-1.0, 0.0, 2.0), Vector3::new(3.0, 2.0, 2.0)));
+	assert_eq!(bounds.center(), Vector3::new(1.0, 1.0, 2.0));
+	assert_eq!(bounds.radius, Some(5.0f64.sqrt()));
+	assert!(bounds.contains(Vector3::new(3.0, 0.0, 2.0)));
+	assert!(!bounds.contains(Vector3::new(3.0, 0.0, 2.5)));
+	assert_eq!(object().bounds().unwrap().max, Vector3::new(8.0, 8.0, 1.0));
+}
+
+#[test]
+fn bounds_union_and_transform() {
+	let a = Bounds::from_points(vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 2.0, 2.0)]).unwrap();
+	let b = Bounds::from_points(vec![Vector3::new(4.0, 0.0, 0.0), Vector3::new(6.0, 2.0, 2.0)]).unwrap();
+	let both = a.union(&b);
+
+	assert!(both.contains_bounds(&a) && both.contains_bounds(&b));
+	assert!(!a.intersects(&b));
+	assert!(a.intersects(&both));
+	// Both spheres of radius sqrt(3) sit 2 from the new center
+	assert_eq!(both.radius, Some(2.0 + 3.0f64.sqrt()));
+
+	let moved = a.transform(&(Matrix4::from_translation(Vector3::new(1.0, 0.0, 0.0)) * Matrix4::from_scale(2.0)));
+	assert_eq!((moved.min, moved.max), (Vector3::new(1.0, 0.0, 0.0), Vector3::new(5.0, 4.0, 4.0)));
+	assert_eq!(moved.radius, Some(2.0 * 3.0f64.sqrt()));
+
+	// The box grows to fit its rotated corners
+	let turned = a.transform(&Matrix4::from_angle_z(Deg(45.0)));
+	assert!((turned.size().x - 8.0f64.sqrt()).abs() < 1e-9);
+}
+
+#[test]
+fn ray_box_intersection() {
+	let bounds = Bounds::from_points(vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0)]).unwrap();
+
+	assert_eq!(bounds.intersect_ray(&Ray::new(Vector3::new(-2.0, 0.5, 0.5), Vector3::new(2.0, 0.0, 0.0))), Some(1.0));
+	assert_eq!(bounds.intersect_ray(&Ray::new(Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.0, 0.0, -1.0))), Some(0.0));
+	assert_eq!(bounds.intersect_ray(&Ray::new(Vector3::new(-2.0, 0.5, 0.5), Vector3::new(-1.0, 0.0, 0.0))), None);
+	assert_eq!(bounds.intersect_ray(&Ray::new(Vector3::new(-2.0, 1.5, 0.5), Vector3::new(1.0, 0.0, 0.0))), None);
+}
+
+#[test]
+fn closest_hit() {
+	let bvh = Bvh::from_object(&object());
+	assert_eq!(bvh.triangles().len(), 2 * 64 + 2 * 16);
+
+	// From above, the upper grid is hit first where it exists
+	let hit = bvh.intersect(&Ray::new(Vector3::new(2.25, 3.75, 5.0), Vector3::new(0.0, 0.0, -2.0))).unwrap();
+	let triangle = &bvh.triangles()[hit.triangle];
+	assert_eq!((hit.distance, triangle.node), (2.0, 1));
+	assert!((hit.barycentric.x + hit.barycentric.y + hit.barycentric.z - 1.0).abs() < 1e-9);
+
+	let hit = bvh.intersect(&Ray::new(Vector3::new(6.5, 6.5, 5.0), Vector3::new(0.0, 0.0, -1.0))).unwrap();
+	assert_eq!((hit.distance, bvh.triangles()[hit.triangle].node), (5.0, 0));
+
+	// Back faces count too
+	let hit = bvh.intersect(&Ray::new(Vector3::new(1.5, 1.5, -1.0), Vector3::new(0.0, 0.0, 1.0))).unwrap();
+	assert_eq!((hit.distance, bvh.triangles()[hit.triangle].node), (1.0, 0));
+
+	assert_eq!(bvh.intersect(&Ray::new(Vector3::new(9.0, 1.0, 5.0), Vector3::new(0.0, 0.0, -1.0))), None);
+	assert_eq!(bvh.intersect(&Ray::new(Vector3::new(1.0, 1.0, 5.0), Vector3::new(0.0, 0.0, 1.0))), None);
+}
+
+#[test]
+fn hits_match_brute_force() {
+	let bvh = Bvh::from_object(&object());
+
+	for i in 0..50 {
+		let t = f64::from(i);
+		let ray = Ray::new(Vector3::new(t * 0.37 % 9.0 - 0.5, t * 0.61 % 9.0 - 0.5, 3.0), Vector3::new(0.1, -0.05, -1.0));
+		let expected = bvh.triangles().iter()
+			.map(|tri| Bvh::from_node(&Node {
+				vertices: tri.corners.iter().map(|&c| common::vertex(c)).collect(),
+				faces: vec![Face::Triangle(Vector3::new(0, 1, 2))],
+				..Default::default()
+			}))
+			.filter_map(|single| single.intersect(&ray).map(|h| h.distance))
+			.fold(None, |closest: Option<f64>, d| Some(closest.map_or(d, |c| c.min(d))));
+
+		assert_eq!(bvh.intersect(&ray).map(|h| h.distance), expected, "ray {}", i);
+	}
+	assert_eq!(Bvh::<f64>::from_node(&Node::<f64, f32>::default()).intersect(&Ray::new(Vector3::zero(), Vector3::unit_z())), None);
+}
@@ -0,0 +1,56 @@
+use cgmath::{
+	Matrix4,
+	Point3,
+	SquareMatrix,
+	Transform as _,
+	Vector3
+};
+
+use meshio::*;
+
+#[test]
+fn new_cameras_look_along_their_node() {
+	let camera = Camera::<f64>::new(LocalizedStringMap::new(), 1.0);
+	assert_eq!((camera.fov, camera.near, camera.far, camera.target), (1.0, 0.1, 1000.0, None));
+
+	let world = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0));
+	assert_eq!(camera.view(world), world.invert().unwrap());
+	assert_eq!(camera.view(Matrix4::from_scale(0.0)), Matrix4::identity());
+}
+
+#[test]
+fn targeted_cameras_look_at_their_target() {
+	let mut camera = Camera::<f64>::new(LocalizedStringMap::new(), 1.0);
+	camera.target = Some(Vector3::new(0.0, 0.0, -5.0));
+
+	let view = camera.view(Matrix4::from_translation(Vector3::new(0.0, 0.0, 5.0)));
+	let target = view.transform_point(Point3::new(0.0, 0.0, -5.0));
+	assert!(target.x.abs() < 1e-9 && target.y.abs() < 1e-9);
+	assert!((target.z + 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn projection_maps_the_clipping_planes() {
+	let camera = Camera::<f64>::new(LocalizedStringMap::new(), 1.0);
+	let projection = camera.projection(2.0);
+
+	let near = projection.transform_point(Point3::new(0.0, 0.0, -0.1));
+	let far = projection.transform_point(Point3::new(0.0, 0.0, -1000.0));
+	assert!((near.z + 1.0).abs() < 1e-9);
+	assert!((far.z - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn conversion_scales_clipping_and_target() {
+	let mut scene = Scene::<f64, f32>::default();
+	let mut camera = Camera::new(LocalizedStringMap::new(), 1.0);
+	camera.target = Some(Vector3::new(1.0, 2.0, 3.0));
+	scene.cameras.push(camera);
+
+	// The MMD axes with half meter units mirror Z and double every length
+	let half_meters = CoordinateSystem { meters_per_unit: 0.5, ..CoordinateSystem::MMD };
+	scene.convert_to(half_meters).unwrap();
+	let camera = &scene.cameras[0];
+	assert_eq!((camera.fov, camera.near, camera.far), (1.0, 0.2, 2000.0));
+	assert_eq!(camera.target, Some(Vector3::new(2.0, 4.0, -6.0)));
+}
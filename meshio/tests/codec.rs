@@ -0,0 +1,124 @@
+use cgmath::{
+	InnerSpace,
+	Vector2,
+	Vector3
+};
+
+use meshio::codec::*;
+
+#[test]
+fn half_round_trip() {
+	for h in 0..=u16::MAX {
+		assert_eq!(encode_half(decode_half(h)), h, "half {:#06x}", h);
+	}
+}
+
+#[test]
+fn half_values() {
+	assert_eq!(decode_half(0x3c00), 1.0);
+	assert_eq!(decode_half(0xc000), -2.0);
+	assert_eq!(decode_half(0x7bff), 65504.0);
+	assert_eq!(decode_half(0x0001), 2.0f32.powi(-24));
+	assert_eq!(decode_half(0x7c00), f32::INFINITY);
+	assert!(decode_half(0x7e00).is_nan());
+
+	assert_eq!(encode_half(1.0e6), 0x7c00);
+	assert_eq!(encode_half(-1.0e-10), 0x8000);
+	// Halfway between 0x3c01 and 0x3c02 rounds to the even mantissa
+	assert_eq!(encode_half(1.0 + 1.5 * 2.0f32.powi(-10)), 0x3c02);
+	// Halfway between zero and the smallest subnormal rounds down to zero
+	assert_eq!(encode_half(2.0f32.powi(-25)), 0x0000);
+	assert_eq!(encode_half(f32::NAN) & 0x7c00, 0x7c00);
+	assert_ne!(encode_half(f32::NAN) & 0x3ff, 0);
+}
+
+#[test]
+fn normalized_round_trip() {
+	for v in 0..=u8::MAX {
+		assert_eq!(encode_unorm8(decode_unorm8(v)), v);
+	}
+	for v in i8::MIN + 1..=i8::MAX {
+		assert_eq!(encode_snorm8(decode_snorm8(v)), v);
+	}
+	for v in 0..=u16::MAX {
+		assert_eq!(encode_unorm16(decode_unorm16(v)), v);
+	}
+	for v in i16::MIN + 1..=i16::MAX {
+		assert_eq!(encode_snorm16(decode_snorm16(v)), v);
+	}
+
+	assert_eq!(decode_snorm8(i8::MIN), -1.0);
+	assert_eq!(decode_snorm16(i16::MIN), -1.0);
+	assert_eq!(encode_unorm8(2.0), 255);
+	assert_eq!(encode_snorm16(-2.0), -32767);
+}
+
+#[test]
+fn octahedral_round_trip() {
+	let directions = [
+		Vector3::new(0.0, 0.0, 1.0),
+		Vector3::new(0.0, 0.0, -1.0),
+		Vector3::new(1.0, 0.0, 0.0),
+		Vector3::new(0.0, -1.0, 0.0),
+		Vector3::new(0.3, -0.4, -0.5).normalize(),
+		Vector3::new(-0.9, 0.1, 0.2).normalize(),
+	];
+
+	for &n in directions.iter() {
+		assert!((decode_octahedral(encode_octahedral(n)) - n).magnitude() < 1e-6);
+		assert!((decode_octahedral_snorm16(encode_octahedral_snorm16(n)) - n).magnitude() < 1e-4);
+		assert!((decode_octahedral_snorm8(encode_octahedral_snorm8(n)) - n).magnitude() < 2e-2);
+	}
+
+	// Codes away from the folded edges survive a decode and encode exactly
+	for x in -126..=126i8 {
+		for y in -126..=126i8 {
+			if i32::from(x).abs() + i32::from(y).abs() == 127 {
+				continue;
+			}
+			let e = Vector2::new(x, y);
+			assert_eq!(encode_octahedral_snorm8(decode_octahedral_snorm8(e)), e);
+		}
+	}
+}
+
+#[test]
+fn weights_sum_to_255() {
+	assert_eq!(encode_weights_unorm8(&[1.0, 1.0, 1.0]).iter().map(|&b| u32::from(b)).sum::<u32>(), 255);
+	assert_eq!(encode_weights_unorm8(&[0.5, 0.25, 0.25, 0.0]), vec![127, 64, 64, 0]);
+	assert_eq!(encode_weights_unorm8(&[2.0, 0.0]), vec![255, 0]);
+	assert_eq!(encode_weights_unorm8(&[0.0, 0.0]), vec![0, 0]);
+
+	for v in 0..=u8::MAX {
+		let bytes = encode_weights_unorm8(&[decode_unorm8(v), decode_unorm8(255 - v)]);
+		assert_eq!(bytes, vec![v, 255 - v]);
+	}
+}
+
+#[test]
+fn m3_round_trip() {
+	for c in 0..=u8::MAX {
+		let v = Vector3::new(c, 255 - c, c / 2);
+		assert_eq!(encode_m3_vector(decode_m3_vector(v)), v);
+	}
+	for c in i16::MIN..=i16::MAX {
+		let v = Vector2::new(c, c.wrapping_neg());
+		assert_eq!(encode_m3_uv(decode_m3_uv(v)), v);
+	}
+
+	assert_eq!(decode_m3_vector(Vector3::new(0, 255, 0)), Vector3::new(-1.0, 1.0, -1.0));
+	assert_eq!(decode_m3_uv(Vector2::new(2048, -1024)), Vector2::new(1.0, -0.5));
+	assert_eq!(encode_m3_sign(decode_m3_sign(0)), 0);
+	assert_eq!(encode_m3_sign(decode_m3_sign(255)), 255);
+	assert_eq!(decode_m3_sign(0), -1.0);
+}
+
+#[test]
+fn ism2_round_trip() {
+	let v = Vector3::new(0x3c00, 0xb800, 0x0000);
+	assert_eq!(decode_ism2_vector(v), Vector3::new(1.0, -0.5, 0.0));
+	assert_eq!(encode_ism2_vector(decode_ism2_vector(v)), v);
+
+	let uv = Vector2::new(0x3800, 0x3555);
+	assert_eq!(encode_ism2_uv(decode_ism2_uv(uv)), uv);
+}
@@ -0,0 +1,91 @@
+use meshio::*;
+
+const C: ColorB = ColorB { red: 0x11, green: 0x22, blue: 0x33, alpha: 0x44 };
+
+fn close(a: &ColorD, b: &ColorD) -> bool {
+	[a.red - b.red, a.green - b.green, a.blue - b.blue, a.alpha - b.alpha].iter().all(|d| d.abs() < 1e-7)
+}
+
+#[test]
+fn packed_byte_orders() {
+	assert_eq!(C.to_rgba_u32(), 0x11223344);
+	assert_eq!(C.to_argb_u32(), 0x44112233);
+	assert_eq!(C.to_bgra_u32(), 0x33221144);
+	assert_eq!(C.to_abgr_u32(), 0x44332211);
+
+	assert_eq!(ColorB::from_rgba_u32(0x11223344), C);
+	assert_eq!(ColorB::from_argb_u32(0x44112233), C);
+	assert_eq!(ColorB::from_bgra_u32(0x33221144), C);
+	assert_eq!(ColorB::from_abgr_u32(0x44332211), C);
+}
+
+#[test]
+fn packed_values_match_byte_parsers() {
+	assert_eq!(le_rgba_b(&C.to_rgba_u32().to_be_bytes()).unwrap().1, C);
+	assert_eq!(le_argb_b(&C.to_argb_u32().to_be_bytes()).unwrap().1, C);
+	assert_eq!(le_bgra_b(&C.to_bgra_u32().to_be_bytes()).unwrap().1, C);
+	assert_eq!(be_argb_b(&C.to_argb_u32().to_be_bytes()).unwrap().1, C);
+	assert_eq!(be_bgra_b(&C.to_bgra_u32().to_be_bytes()).unwrap().1, C);
+	// RGBA bytes read as a little endian integer
+	assert_eq!(ColorB::from_abgr_u32(u32::from_le_bytes([0x11, 0x22, 0x33, 0x44])), C);
+}
+
+#[test]
+fn srgb_linear_round_trip() {
+	let srgb = ColorD { red: 0.0, green: 0.04045, blue: 1.0, alpha: 0.5 };
+	let linear = srgb.to_linear();
+
+	assert_eq!(linear.red, 0.0);
+	assert!((linear.green - 0.04045 / 12.92).abs() < 1e-12);
+	assert!((linear.blue - 1.0).abs() < 1e-12);
+	assert_eq!(linear.alpha, 0.5);
+	// The standard's two thresholds do not quite meet, so 0.04045 comes back off by about 3e-8
+	assert!(close(&linear.to_srgb(), &srgb));
+
+	// Mid gray is far darker in linear light
+	assert!((ColorD { red: 0.5, green: 0.5, blue: 0.5, alpha: 1.0 }.to_linear().red - 0.21404).abs() < 1e-5);
+}
+
+#[test]
+fn bytes_round_trip() {
+	let color = ColorF::from_bytes(&C);
+	assert_eq!(color.red, 17.0 / 255.0);
+	assert_eq!(color.to_bytes(), C);
+	assert_eq!(ColorF { red: -1.0, green: 2.0, blue: 0.5, alpha: 1.0 }.to_bytes(), ColorB { red: 0, green: 255, blue: 128, alpha: 255 });
+}
+
+#[test]
+fn premultiplied_alpha() {
+	let color = ColorD { red: 0.5, green: 1.0, blue: 0.25, alpha: 0.5 };
+	let premultiplied = color.premultiply();
+
+	assert_eq!(premultiplied, ColorD { red: 0.25, green: 0.5, blue: 0.125, alpha: 0.5 });
+	assert_eq!(premultiplied.unpremultiply(), color);
+
+	let clear = ColorD { alpha: 0.0, ..color };
+	assert_eq!(clear.premultiply(), ColorD { red: 0.0, green: 0.0, blue: 0.0, alpha: 0.0 });
+	assert_eq!(clear.unpremultiply(), ColorD { red: 0.0, green: 0.0, blue: 0.0, alpha: 0.0 });
+}
+
+#[test]
+fn hsv_round_trip() {
+	let cases = [
+		(ColorD { red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0 }, Hsv { hue: 0.0, saturation: 1.0, value: 1.0 }),
+		(ColorD { red: 0.5, green: 1.0, blue: 0.0, alpha: 1.0 }, Hsv { hue: 90.0, saturation: 1.0, value: 1.0 }),
+		(ColorD { red: 0.25, green: 0.5, blue: 0.5, alpha: 1.0 }, Hsv { hue: 180.0, saturation: 0.5, value: 0.5 }),
+		(ColorD { red: 0.5, green: 0.0, blue: 1.0, alpha: 1.0 }, Hsv { hue: 270.0, saturation: 1.0, value: 1.0 }),
+		(ColorD { red: 1.0, green: 0.0, blue: 0.5, alpha: 1.0 }, Hsv { hue: 330.0, saturation: 1.0, value: 1.0 }),
+		(ColorD { red: 0.5, green: 0.5, blue: 0.5, alpha: 1.0 }, Hsv { hue: 0.0, saturation: 0.0, value: 0.5 }),
+		(ColorD { red: 0.0, green: 0.0, blue: 0.0, alpha: 1.0 }, Hsv { hue: 0.0, saturation: 0.0, value: 0.0 }),
+	];
+
+	for (color, hsv) in cases.iter() {
+		assert_eq!(color.to_hsv(), *hsv);
+		assert!(close(&ColorD::from_hsv(*hsv, 1.0), color), "{:?} gave {:?}", hsv, ColorD::from_hsv(*hsv, 1.0));
+	}
+
+	// Hues wrap around the circle
+	let red = ColorD { red: 1.0, green: 0.0, blue: 0.0, alpha: 0.5 };
+	assert!(close(&ColorD::from_hsv(Hsv { hue: 360.0, saturation: 1.0, value: 1.0 }, 0.5), &red));
+	assert!(close(&ColorD::from_hsv(Hsv { hue: -360.0, saturation: 1.0, value: 1.0 }, 0.5), &red));
+}
@@ -0,0 +1,35 @@
+//! Fixtures shared by the integration tests, which each use only some of them
+#![allow(clippy::redundant_field_names, dead_code)]
+
+use cgmath::{
+	BaseFloat,
+	Vector2,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use meshio::{
+	SkinWeights,
+	Vertex
+};
+
+/// Builds a vertex at `position` facing +Z, without texture coordinates, colors or weights
+pub fn vertex<P: BaseFloat>(position: Vector3<P>) -> Vertex<P, f32> {
+	Vertex {
+		position: position,
+		normals: [Vector3::unit_z(), Vector3::zero()],
+		tangent: Vector4::zero(),
+		uvs: Vec::new(),
+		colors: Vec::new(),
+		weights: SkinWeights::default(),
+	}
+}
+
+/// Builds a vertex at `(x, y)` in the XY plane facing +Z, textured with its own position
+pub fn planar(x: f32, y: f32) -> Vertex<f32, f32> {
+	Vertex {
+		uvs: vec![Vector2::new(x, y)],
+		..vertex(Vector3::new(x, y, 0.0))
+	}
+}
@@ -0,0 +1,86 @@
+use cgmath::{
+	Deg,
+	InnerSpace,
+	Matrix4,
+	Quaternion,
+	Rotation3,
+	Transform as _,
+	Vector3
+};
+
+use meshio::*;
+
+fn close(a: Vector3<f64>, b: Vector3<f64>) -> bool {
+	(a - b).magnitude() < 1e-9
+}
+
+/// A GLTF scene of a turned directional light, a camera below it and an empty below that
+fn scene() -> Scene<f64, f32> {
+	let mut scene = Scene::default();
+	scene.lights.push(Light::new(LocalizedStringMap::new(), LightKind::Directional));
+	scene.cameras.push(Camera::new(LocalizedStringMap::new(), 1.0));
+
+	let mut light = SceneNode::new(LocalizedStringMap::new());
+	light.light = Some(0);
+	light.transform = Transform {
+		translation: Vector3::new(1.0, 2.0, 3.0),
+		rotation: Quaternion::from_angle_y(Deg(30.0)) * Quaternion::from_angle_x(Deg(-45.0)),
+		scale: Vector3::new(1.0, 2.0, 3.0),
+	};
+
+	let mut camera = SceneNode::new(LocalizedStringMap::new());
+	camera.parent = Some(0);
+	camera.camera = Some(0);
+	camera.transform.translation = Vector3::new(0.0, 1.0, -2.0);
+	camera.transform.rotation = Quaternion::from_angle_z(Deg(20.0));
+
+	let mut empty = SceneNode::new(LocalizedStringMap::new());
+	empty.parent = Some(1);
+	empty.transform.translation = Vector3::new(0.5, 0.0, 1.0);
+
+	scene.add_node(light);
+	scene.add_node(camera);
+	scene.add_node(empty);
+	scene
+}
+
+/// Returns every node's world position and the world direction of its local -Z axis
+fn placement(scene: &Scene<f64, f32>) -> Vec<(Vector3<f64>, Vector3<f64>)> {
+	scene.world_matrices().unwrap().iter()
+		.map(|m: &Matrix4<f64>| (m.w.truncate(), m.transform_vector(-Vector3::unit_z()).normalize()))
+		.collect()
+}
+
+#[test]
+fn lights_and_cameras_keep_pointing_the_same_way() {
+	for &target in [CoordinateSystem::MMD, CoordinateSystem::SOURCE].iter() {
+		let mut scene = scene();
+		let before = placement(&scene);
+		let axes = CoordinateSystem::GLTF.reorientation::<f64>(&target);
+		let scale = CoordinateSystem::GLTF.scale_to::<f64>(&target);
+
+		scene.convert_to(target).unwrap();
+		let after = placement(&scene);
+
+		for (i, (&(p0, d0), &(p1, d1))) in before.iter().zip(after.iter()).enumerate() {
+			assert!(close(axes * p0 * scale, p1), "node {} moved to {:?}", i, p1);
+			if i < 2 {
+				assert!(close(axes * d0, d1), "node {} turned to {:?}", i, d1);
+			}
+		}
+		assert_eq!(scene.nodes[0].transform.scale, Vector3::new(1.0, 2.0, 3.0));
+	}
+}
+
+#[test]
+fn invalid_systems_are_rejected() {
+	let mut invalid = CoordinateSystem::GLTF;
+	invalid.forward = Axis::NegativeY;
+
+	let mut scene = scene();
+	assert_eq!(scene.convert_to(invalid), Err(InvalidCoordinateSystem(invalid)));
+	assert_eq!(scene, self::scene());
+
+	scene.coordinate_system = invalid;
+	assert_eq!(scene.convert_to(CoordinateSystem::MMD), Err(InvalidCoordinateSystem(invalid)));
+}
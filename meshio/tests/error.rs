@@ -0,0 +1,80 @@
+#![allow(clippy::redundant_field_names)]
+
+use nom::{
+	error::ErrorKind,
+	Err,
+	Needed
+};
+
+use meshio::*;
+
+fn line_column(line: usize, column: usize) -> Position {
+	Position::LineColumn { line: line, column: column }
+}
+
+#[test]
+fn line_column_counts_from_one() {
+	let input = b"ab\ncd\n\nef";
+	assert_eq!(Position::line_column(input, 0), line_column(1, 1));
+	assert_eq!(Position::line_column(input, 2), line_column(1, 3));
+	assert_eq!(Position::line_column(input, 3), line_column(2, 1));
+	assert_eq!(Position::line_column(input, 7), line_column(4, 1));
+	assert_eq!(line_column(4, 1).to_string(), "line 4, column 1");
+}
+
+#[test]
+fn line_column_with_crlf() {
+	let input = b"ab\r\ncd\r\n";
+	// The carriage return still belongs to the line it ends
+	assert_eq!(Position::line_column(input, 2), line_column(1, 3));
+	assert_eq!(Position::line_column(input, 4), line_column(2, 1));
+	assert_eq!(Position::line_column(input, 5), line_column(2, 2));
+}
+
+#[test]
+fn line_column_past_the_end() {
+	let input = b"ab\ncd";
+	assert_eq!(Position::line_column(input, 5), line_column(2, 3));
+	assert_eq!(Position::line_column(input, 100), line_column(2, 3));
+	assert_eq!(Position::line_column(b"", 1), line_column(1, 1));
+}
+
+#[test]
+fn incomplete_input_is_eof() {
+	let input = b"PMX \x00\x00";
+	let errors = [
+		Error::parse(Format::Pmx, "header", input, Err::Incomplete(Needed::Size(4))),
+		Error::parse(Format::Pmx, "header", input, Err::Error((&input[6..], ErrorKind::Eof))),
+		Error::parse(Format::Pmx, "header", input, Err::Failure((&input[4..], ErrorKind::Eof))),
+	];
+
+	for error in errors.iter() {
+		match error {
+			Error::UnexpectedEof { format, context, position } => {
+				assert_eq!((*format, context.as_str(), *position), (Format::Pmx, "header", Position::Offset(6)));
+			},
+			other => panic!("expected an unexpected end of input, got {:?}", other),
+		}
+	}
+	assert_eq!(errors[0].to_string(), "unexpected end of input in PMX header at byte 6");
+}
+
+#[test]
+fn other_errors_are_corrupt_data() {
+	let input = b"PMX \x00\x00";
+	let error = Error::parse(Format::Pmx, "header", input, Err::Failure((&input[4..], ErrorKind::Tag)));
+
+	match &error {
+		Error::Corrupt { position, .. } => assert_eq!(*position, Position::Offset(4)),
+		other => panic!("expected corrupt data, got {:?}", other),
+	}
+	assert_eq!(error.format(), Some(Format::Pmx));
+	assert_eq!(error.position(), Some(Position::Offset(4)));
+	assert_eq!(error.to_string(), "invalid PMX header at byte 4: tag");
+
+	let text = b"v 1 2\nv x";
+	let error = Error::parse_text(Format::Obj, "vertex", text, Err::Error((&text[8..], ErrorKind::Float)));
+	assert_eq!(error.position(), Some(line_column(2, 3)));
+	let error = Error::parse_text(Format::Obj, "vertex", text, Err::Incomplete(Needed::Unknown));
+	assert_eq!(error.position(), Some(line_column(2, 4)));
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use meshio::*;
+
+/// Imports every file as an empty scene named after its first byte
+struct Stub(Format);
+
+impl Importer for Stub {
+	fn format(&self) -> Format {
+		self.0
+	}
+
+	fn import(&self, input: &[u8]) -> Result<SceneF> {
+		let mut scene = SceneF::default();
+		scene.name.insert(Language::English, format!("{} {}", self.0.name(), input[0]));
+		Ok(scene)
+	}
+}
+
+impl Exporter for Stub {
+	fn format(&self) -> Format {
+		self.0
+	}
+
+	fn export(&self, _scene: &SceneF) -> Result<Vec<u8>> {
+		Ok(self.0.name().as_bytes().to_vec())
+	}
+}
+
+fn path(name: &str) -> Option<&Path> {
+	Some(Path::new(name))
+}
+
+#[test]
+fn signatures_win_over_extensions() {
+	assert_eq!(detect(b"PMX \x00\x00\x00\x40", path("model.obj")), Some(Format::Pmx));
+	assert_eq!(detect(b"IDST", path("model.mdl")), Some(Format::ValveMdl));
+	assert_eq!(detect(b"MDLX", None), Some(Format::Mdx));
+	assert_eq!(detect(b"33DM", path("model")), Some(Format::M3));
+}
+
+#[test]
+fn extensions_are_a_fallback() {
+	assert_eq!(detect(b"v 0 0 0", path("dir/Model.OBJ")), Some(Format::Obj));
+	assert_eq!(detect(b"", path("model.mesh")), Some(Format::Xps));
+	assert_eq!(detect(b"solid", path("model.stl")), Some(Format::Stl));
+	// Formats with a signature are still found by extension if their file lacks it
+	assert_eq!(detect(b"", path("model.pmx")), Some(Format::Pmx));
+}
+
+#[test]
+fn untagged_mdl_is_blizzard_text() {
+	assert_eq!(detect(b"Version {", path("model.mdl")), Some(Format::Mdl));
+}
+
+#[test]
+fn unknown_files() {
+	assert_eq!(detect(b"????", None), None);
+	assert_eq!(detect(b"????", path("model")), None);
+	assert_eq!(detect(b"????", path("model.txt")), None);
+
+	match Registry::new().import(b"????", path("model.txt")) {
+		Err(Error::UnknownFormat) => {},
+		other => panic!("expected an unknown format, got {:?}", other.map(|s| s.name)),
+	}
+}
+
+#[test]
+fn registry_dispatches_by_format() {
+	let mut registry = Registry::new();
+	registry.register(Stub(Format::Pmx));
+	// Registering a format again replaces its importer
+	registry.register(Stub(Format::Pmx));
+	registry.register_exporter(Stub(Format::Obj));
+
+	assert_eq!(registry.importer(Format::Pmx).map(|i| i.format()), Some(Format::Pmx));
+	assert!(registry.importer(Format::Obj).is_none());
+	assert_eq!(registry.detect(b"PMX ", None).map(|i| i.format()), Some(Format::Pmx));
+
+	let scene = registry.import(b"PMX ", path("model.obj")).unwrap();
+	assert_eq!(scene.name.localized(&Language::English), Some("PMX 80"));
+	// A detected format without an importer is unknown to the registry
+	match registry.import(b"v 0 0 0", path("model.obj")) {
+		Err(Error::UnknownFormat) => {},
+		other => panic!("expected an unknown format, got {:?}", other.map(|s| s.name)),
+	}
+
+	assert_eq!(registry.export(&scene, Format::Obj).unwrap(), b"OBJ");
+	assert!(registry.exporter(Format::Pmx).is_none());
+	match registry.export(&scene, Format::Pmx) {
+		Err(Error::NoExporter(format)) => assert_eq!(format, Format::Pmx),
+		other => panic!("expected no exporter, got {:?}", other),
+	}
+}
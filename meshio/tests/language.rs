@@ -0,0 +1,37 @@
+use meshio::*;
+
+#[test]
+fn from_tag_maps_known_languages() {
+	assert_eq!(Language::from_tag("EN-us"), Language::English);
+	assert_eq!(Language::from_tag("ja_JP"), Language::Japanese);
+	assert_eq!(Language::from_tag("ko"), Language::Korean);
+	assert_eq!(Language::from_tag("zh"), Language::ChineseSimplified);
+	assert_eq!(Language::from_tag("zh-Hans-HK"), Language::ChineseSimplified);
+	assert_eq!(Language::from_tag("zh-Hant"), Language::ChineseTraditional);
+	assert_eq!(Language::from_tag("zh_TW"), Language::ChineseTraditional);
+}
+
+#[test]
+fn from_tag_normalizes_other_languages() {
+	assert_eq!(Language::from_tag("DE"), Language::Other("de".to_owned()));
+	assert_eq!(Language::from_tag("PT_br"), Language::from_tag("pt-BR"));
+	assert_eq!(Language::from_tag("sr-latn-rs").tag(), "sr-Latn-RS");
+	assert_eq!(Language::from_tag("de-CH-1996").tag(), "de-CH-1996");
+	assert_eq!(Language::from_tag("EN-x-Pirate"), Language::English);
+	assert_eq!(Language::from_tag("fr-x-AB-cdef").tag(), "fr-x-ab-cdef");
+}
+
+#[test]
+fn lookup_falls_back_along_the_chain() {
+	let mut name = LocalizedStringMap::new();
+	name.insert(Language::ChineseTraditional, "頭髮".to_owned());
+	name.insert(Language::Japanese, String::new());
+	name.insert(Language::Other("de".to_owned()), "Haar".to_owned());
+
+	assert_eq!(name.localized(&Language::ChineseSimplified), Some("頭髮"));
+	// Empty strings are skipped, then the first language in sort order is used
+	assert_eq!(name.localized(&Language::Japanese), Some("頭髮"));
+	name.insert(Language::English, "hair".to_owned());
+	assert_eq!(name.localized(&Language::Japanese), Some("hair"));
+	assert_eq!(LocalizedStringMap::new().localized(&Language::English), None);
+}
@@ -0,0 +1,145 @@
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use meshio::*;
+
+mod common;
+
+/// Builds a skinned vertex whose values are all exact in the formats of [`mixed`]
+fn vertex(i: u8) -> Vertex<f32, f32> {
+	let f = f32::from(i);
+
+	Vertex {
+		normals: [Vector3::new(0.0, 1.0, -1.0), Vector3::zero()],
+		tangent: Vector4::new(1.0, 0.0, 0.0, -1.0),
+		uvs: vec![Vector2::new(0.5, f * 0.25)],
+		colors: vec![ColorF { red: 1.0, green: 0.0, blue: 51.0 / 255.0, alpha: 1.0 }],
+		weights: SkinWeights::from_slices(&[3, 1], &[153.0 / 255.0, 102.0 / 255.0]),
+		..common::vertex(Vector3::new(f, -2.5, 1.0e6))
+	}
+}
+
+/// Interleaves every supported vertex field in a different component format
+fn mixed(byte_order: ByteOrder) -> VertexLayout {
+	let mut layout = VertexLayout::new(byte_order);
+	layout.push(Semantic::Position, 0, ComponentFormat::F32, 3);
+	layout.push(Semantic::Normal, 0, ComponentFormat::Snorm8, 3);
+	layout.push(Semantic::Tangent, 0, ComponentFormat::Snorm16, 4);
+	layout.push(Semantic::TexCoord, 0, ComponentFormat::F16, 2);
+	layout.push(Semantic::Color, 0, ComponentFormat::Unorm8, 4);
+	layout.push(Semantic::Joints, 0, ComponentFormat::U16, 4);
+	layout.push(Semantic::Weights, 0, ComponentFormat::Unorm8, 4);
+	layout
+}
+
+#[test]
+fn mixed_formats_round_trip() {
+	let vertices: Vec<Vertex<f32, f32>> = (0..3).map(vertex).collect();
+
+	for &order in [ByteOrder::Little, ByteOrder::Big].iter() {
+		let layout = mixed(order);
+		let bytes = layout.encode_vertices(&vertices).unwrap();
+		assert_eq!(layout.stride, 12 + 3 + 8 + 4 + 4 + 8 + 4);
+		assert_eq!(bytes.len(), 3 * layout.stride);
+
+		let decoded = layout.decode_vertices(&bytes, 3).unwrap();
+		assert_eq!(decoded, vertices);
+		assert_eq!(layout.encode_vertices(&decoded).unwrap(), bytes);
+	}
+}
+
+#[test]
+fn big_endian_byte_layout() {
+	let mut layout = VertexLayout::new(ByteOrder::Big);
+	layout.push(Semantic::Position, 0, ComponentFormat::F32, 1);
+	layout.push(Semantic::TexCoord, 0, ComponentFormat::F16, 1);
+	layout.push(Semantic::Other("ID".to_owned()), 0, ComponentFormat::U16, 1);
+	layout.push(Semantic::Other("ID".to_owned()), 1, ComponentFormat::I32, 1);
+
+	let bytes = [0x3f, 0x80, 0x00, 0x00, 0x3c, 0x00, 0x12, 0x34, 0xff, 0xff, 0xff, 0xfe];
+	let streams = layout.decode(&bytes, 1).unwrap();
+	let values: Vec<f32> = streams.iter().map(|s| s.values[0]).collect();
+
+	assert_eq!(values, [1.0, 1.0, 4660.0, -2.0]);
+	assert_eq!(layout.encode(&streams).unwrap(), bytes);
+}
+
+#[test]
+fn buffer_too_short() {
+	let mut layout = VertexLayout::new(ByteOrder::Little);
+	layout.push(Semantic::Position, 0, ComponentFormat::F32, 3);
+	// Padding after the last attribute that the final vertex may omit
+	layout.stride = 16;
+
+	assert_eq!(layout.decode(&[0; 27], 2), Err(LayoutError::BufferTooShort { needed: 28, len: 27 }));
+	assert_eq!(layout.decode(&[0; 28], 2).unwrap()[0].len(), 2);
+	assert!(layout.decode(&[], 0).unwrap()[0].is_empty());
+}
+
+#[test]
+fn invalid_attributes() {
+	let mut layout = VertexLayout::new(ByteOrder::Little);
+	layout.push(Semantic::Position, 0, ComponentFormat::F32, 3);
+	layout.push(Semantic::Normal, 0, ComponentFormat::F32, 3);
+	layout.stride = 20;
+
+	assert_eq!(layout.validate(), Err(LayoutError::OutsideStride { attribute: 1 }));
+	assert_eq!(layout.decode(&[0; 64], 1), Err(LayoutError::OutsideStride { attribute: 1 }));
+	assert_eq!(layout.encode(&[]), Err(LayoutError::OutsideStride { attribute: 1 }));
+
+	layout.stride = 24;
+	layout.attributes[1].components = 5;
+	assert_eq!(layout.validate(), Err(LayoutError::ComponentCount { attribute: 1, components: 5 }));
+}
+
+#[test]
+fn encode_needs_matching_streams() {
+	let layout = mixed(ByteOrder::Little);
+	let mut streams = layout.streams_from_vertices(&[vertex(0), vertex(1)]);
+
+	streams[2].values.truncate(4);
+	assert_eq!(layout.encode(&streams), Err(LayoutError::StreamLength { semantic: Semantic::Tangent, index: 0 }));
+
+	streams.remove(3);
+	assert_eq!(layout.encode(&streams), Err(LayoutError::MissingStream { semantic: Semantic::TexCoord, index: 0 }));
+}
+
+#[test]
+fn joints_pair_with_weights_of_the_same_index() {
+	let stream = |semantic: Semantic, index: u32, values: &[f32]| {
+		let mut stream = Stream::new(semantic, index, values.len());
+		stream.push(values);
+		stream
+	};
+	// Listed out of order, with joint 5 repeated across sets and a joint set with no weights
+	let streams = [
+		stream(Semantic::Weights, 1, &[0.125, 0.1875]),
+		stream(Semantic::Joints, 2, &[9.0]),
+		stream(Semantic::Joints, 1, &[5.0, 6.0]),
+		stream(Semantic::Weights, 0, &[0.5, 0.125, 0.0625]),
+		stream(Semantic::Joints, 0, &[4.0, 5.0, 7.0]),
+	];
+
+	let vertices = vertices_from_streams(&streams, 1);
+	let mut weights = vertices[0].weights.clone();
+	weights.sort();
+	let pairs: Vec<(usize, f32)> = weights.influences.iter().map(|i| (i.joint, i.weight)).collect();
+	assert_eq!(pairs, [(4, 0.5), (5, 0.25), (6, 0.1875), (7, 0.0625)]);
+
+	// Writing splits the influences heaviest first over the sets in index order
+	let mut layout = VertexLayout::new(ByteOrder::Little);
+	layout.push(Semantic::Joints, 1, ComponentFormat::U8, 2);
+	layout.push(Semantic::Joints, 0, ComponentFormat::U8, 2);
+	layout.push(Semantic::Weights, 0, ComponentFormat::F32, 2);
+	layout.push(Semantic::Weights, 1, ComponentFormat::F32, 2);
+	let written = layout.streams_from_vertices(&vertices);
+
+	assert_eq!(written[1].values, [4.0, 5.0]);
+	assert_eq!(written[0].values, [6.0, 7.0]);
+	assert_eq!(written[2].values, [0.5, 0.25]);
+	assert_eq!(written[3].values, [0.1875, 0.0625]);
+}
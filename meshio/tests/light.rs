@@ -0,0 +1,39 @@
+use meshio::*;
+
+#[test]
+fn new_lights_are_white_without_falloff() {
+	let light = Light::<f32>::new(LocalizedStringMap::new(), LightKind::Point);
+	assert_eq!(light.color, ColorF { red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0 });
+	assert_eq!(light.intensity, 1.0);
+	assert_eq!(light.attenuation, None);
+	assert_eq!(light.intensity_at(1.0e6), 1.0);
+}
+
+#[test]
+fn intensity_falls_off_linearly() {
+	let mut light = Light::<f32>::new(LocalizedStringMap::new(), LightKind::Spot { inner_angle: 0.25, outer_angle: 0.5 });
+	light.intensity = 4.0;
+	light.attenuation = Some(2.0..6.0);
+
+	assert_eq!(light.intensity_at(1.0), 4.0);
+	assert_eq!(light.intensity_at(2.0), 4.0);
+	assert_eq!(light.intensity_at(3.0), 3.0);
+	assert_eq!(light.intensity_at(6.0), 0.0);
+	assert_eq!(light.intensity_at(9.0), 0.0);
+}
+
+#[test]
+fn conversion_scales_attenuation() {
+	let mut scene = Scene::<f64, f32>::default();
+	let mut light = Light::new(LocalizedStringMap::new(), LightKind::Spot { inner_angle: 0.25, outer_angle: 0.5 });
+	light.attenuation = Some(1.0..3.0);
+	scene.lights.push(light);
+
+	let half_meters = CoordinateSystem { meters_per_unit: 0.5, ..CoordinateSystem::MMD };
+	scene.convert_to(half_meters).unwrap();
+	let light = &scene.lights[0];
+	assert_eq!(light.attenuation, Some(2.0..6.0));
+	// Angles and intensity do not depend on the unit
+	assert_eq!(light.kind, LightKind::Spot { inner_angle: 0.25, outer_angle: 0.5 });
+	assert_eq!(light.intensity, 1.0);
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::redundant_field_names)]
+
+use cgmath::{
+	InnerSpace,
+	Vector2,
+	Vector3,
+	Zero
+};
+
+use meshio::*;
+
+mod common;
+
+fn offset(vertex: usize, position: Vector3<f32>, normal: Vector3<f32>, uv: Vector2<f32>) -> MorphOffset<f32> {
+	MorphOffset { node: 0, vertex: vertex, position: position, normal: normal, uv: uv }
+}
+
+/// A triangle with a morph raising and tilting its second vertex, and one sliding the second
+/// UV set of its third vertex
+fn object() -> Object<f32, f32> {
+	let node = Node {
+		vertices: [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)].iter()
+			.map(|&(x, y)| {
+				let mut vertex = common::planar(x, y);
+				vertex.uvs.push(Vector2::zero());
+				vertex
+			})
+			.collect(),
+		faces: vec![Face::Triangle(Vector3::new(0, 1, 2))],
+		..Default::default()
+	};
+	let raise = Morph {
+		offsets: vec![offset(1, Vector3::new(0.0, 0.0, 2.0), Vector3::new(2.0, 0.0, 0.0), Vector2::zero())],
+		..Default::default()
+	};
+	let slide = Morph {
+		uv_set: 1,
+		offsets: vec![offset(2, Vector3::zero(), Vector3::zero(), Vector2::new(0.5, -1.0))],
+		..Default::default()
+	};
+
+	Object {
+		nodes: vec![node],
+		morphs: vec![raise, slide],
+		..Default::default()
+	}
+}
+
+#[test]
+fn weighted_morphs_displace_vertices() {
+	let object = object();
+	let nodes = object.morphed(&[(0, 0.5), (1, 0.5), (1, 0.25)]);
+	let vertices = &nodes[0].vertices;
+
+	assert_eq!(vertices[1].position, Vector3::new(1.0, 0.0, 1.0));
+	assert_eq!(vertices[1].normals[0], Vector3::new(1.0, 0.0, 1.0).normalize());
+	assert_eq!(vertices[2].uv(1), Vector2::new(0.375, -0.75));
+	assert_eq!(vertices[2].uv(0), Vector2::new(0.0, 1.0));
+	// Untouched vertices and the object itself stay as they were
+	assert_eq!(vertices[0], object.nodes[0].vertices[0]);
+	assert_eq!(object.nodes[0].vertices[1].position, Vector3::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn invalid_indices_are_ignored() {
+	let mut object = object();
+	object.morphs[1].uv_set = 2;
+	let stray = MorphOffset { node: 1, ..object.morphs[0].offsets[0].clone() };
+	object.morphs[0].offsets.push(stray);
+
+	assert_eq!(object.morphed(&[(1, 1.0), (2, 1.0)]), object.nodes);
+	assert_eq!(object.morphed(&[(0, -1.0)])[0].vertices[1].position, Vector3::new(1.0, 0.0, -2.0));
+}
@@ -0,0 +1,110 @@
+use cgmath::{
+	Deg,
+	InnerSpace,
+	Rad,
+	Vector2,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use meshio::*;
+
+mod common;
+
+/// Two unit squares folded 90 degrees along the shared edge on the Y axis, facing +Z and -X
+fn fold() -> Node<f32, f32> {
+	Node {
+		vertices: [(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (1.0, 1.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, -1.0), (0.0, 1.0, -1.0)]
+			.iter()
+			.map(|&(x, y, z)| Vertex {
+				normals: [Vector3::zero(), Vector3::zero()],
+				uvs: vec![Vector2::new(x + z, y)],
+				..common::vertex(Vector3::new(x, y, z))
+			})
+			.collect(),
+		faces: vec![Face::Quad(Vector4::new(0, 1, 2, 3)), Face::Quad(Vector4::new(4, 0, 3, 5))],
+		..Default::default()
+	}
+}
+
+fn close(a: Vector3<f32>, b: Vector3<f32>) -> bool {
+	(a - b).magnitude() < 1e-5
+}
+
+#[test]
+fn smooth_normals_average_across_the_fold() {
+	let mut node = fold();
+	node.compute_normals(None, None);
+
+	let diagonal = Vector3::new(-1.0, 0.0, 1.0).normalize();
+	assert_eq!(node.vertices.len(), 6);
+	assert!(close(node.vertices[0].normals[0], diagonal));
+	assert!(close(node.vertices[1].normals[0], Vector3::unit_z()));
+	assert!(close(node.vertices[4].normals[0], -Vector3::unit_x()));
+}
+
+#[test]
+fn crease_angle_splits_shared_vertices() {
+	let mut node = fold();
+	node.compute_normals(Some(Rad::from(Deg(45.0)).0), None);
+
+	// The edge vertices are copied for the second face, which is repointed at the copies
+	assert_eq!(node.vertices.len(), 8);
+	assert!(close(node.vertices[0].normals[0], Vector3::unit_z()));
+	assert!(close(node.vertices[3].normals[0], Vector3::unit_z()));
+	let side = node.faces[1].indices();
+	assert_eq!(side[0], 4);
+	assert!(side[1] >= 6 && side[2] >= 6);
+	assert!(side.iter().all(|&i| close(node.vertices[i as usize].normals[0], -Vector3::unit_x())));
+
+	// Below the crease angle everything is smoothed again
+	let mut node = fold();
+	node.compute_normals(Some(Rad::from(Deg(95.0)).0), None);
+	assert_eq!(node.vertices.len(), 6);
+}
+
+#[test]
+fn smoothing_groups_must_share_a_bit() {
+	let mut node = fold();
+	node.compute_normals(None, Some(&[0b01, 0b11]));
+	assert_eq!(node.vertices.len(), 6);
+
+	let mut node = fold();
+	node.compute_normals(None, Some(&[0b01, 0b10]));
+	assert_eq!(node.vertices.len(), 8);
+
+	// A zero mask keeps the face flat even against itself
+	let mut node = fold();
+	node.compute_normals(None, Some(&[0, 0]));
+	assert_eq!(node.vertices.len(), 8);
+	assert!(close(node.vertices[0].normals[0], Vector3::unit_z()));
+}
+
+#[test]
+fn out_of_range_faces_are_skipped() {
+	let mut node = fold();
+	node.faces.push(Face::Triangle(Vector3::new(0, 1, 9)));
+	node.compute_normals(None, None);
+
+	assert_eq!(node.faces[2], Face::Triangle(Vector3::new(0, 1, 9)));
+	assert!(close(node.vertices[1].normals[0], Vector3::unit_z()));
+}
+
+#[test]
+fn tangents_follow_the_u_axis() {
+	let mut node = fold();
+	node.compute_normals(Some(0.1), None);
+	node.compute_tangents();
+
+	for &i in node.faces[0].indices().iter() {
+		let v = &node.vertices[i as usize];
+		assert!(close(v.tangent.truncate(), Vector3::unit_x()), "vertex {} has tangent {:?}", i, v.tangent);
+		assert_eq!(v.tangent.w, 1.0);
+	}
+
+	// U grows towards +Z on the side face
+	let v = &node.vertices[4];
+	assert!(close(v.tangent.truncate(), Vector3::unit_z()));
+	assert_eq!(v.tangent.w, 1.0);
+}
@@ -0,0 +1,54 @@
+use cgmath::Vector3;
+
+use meshio::{
+	physics::*,
+	*
+};
+
+fn body(name: &str, joint: Option<usize>) -> RigidBody<f32> {
+	let mut names = LocalizedStringMap::new();
+	names.insert(Language::English, name.to_owned());
+	let mut body = RigidBody::new(names, Shape::Sphere { radius: 1.0 });
+	body.joint = joint;
+	body
+}
+
+#[test]
+fn new_bodies_collide_with_everything() {
+	let body = body("hair", None);
+	assert_eq!((body.group, body.mask), (!0, !0));
+	assert_eq!(body.mode, BodyMode::Kinematic);
+	assert_eq!(body.mass, 1.0);
+}
+
+#[test]
+fn limits() {
+	let locked = Limits::<f32>::locked();
+	let free = Limits::<f32>::free();
+	assert!(locked.is_locked(1) && !locked.is_free(1));
+	assert!(free.is_free(2) && !free.is_locked(2));
+
+	let constraint = Constraint::<f32>::new(LocalizedStringMap::new(), ConstraintKind::Hinge, [0, 1]);
+	assert_eq!((constraint.linear, constraint.angular), (locked, free));
+	assert_eq!(constraint.linear_spring, None);
+
+	let mut hinge = free;
+	hinge.lower = Vector3::new(-1.0, 0.0, 0.0);
+	hinge.upper = Vector3::new(1.0, 0.0, 0.0);
+	assert!(!hinge.is_locked(0) && !hinge.is_free(0) && hinge.is_locked(1));
+}
+
+#[test]
+fn lookups_and_remap() {
+	let mut physics = Physics::default();
+	assert!(physics.is_empty());
+	physics.bodies = vec![body("hair", Some(2)), body("skirt", Some(0)), body("ribbon", Some(2)), body("cape", Some(7))];
+
+	assert_eq!(physics.find_body("ribbon"), Some(2));
+	assert_eq!(physics.find_body("tail"), None);
+	assert_eq!(physics.bodies_of(2).collect::<Vec<_>>(), [0, 2]);
+
+	physics.remap(&[2, 1, 0]);
+	let joints: Vec<Option<usize>> = physics.bodies.iter().map(|b| b.joint).collect();
+	assert_eq!(joints, [Some(0), Some(2), Some(0), None]);
+}
@@ -0,0 +1,106 @@
+#![allow(clippy::redundant_field_names)]
+
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4,
+	Zero
+};
+
+use meshio::{
+	process::*,
+	*
+};
+
+mod common;
+
+/// A unit square of two triangles that each have their own copy of the shared edge
+fn square() -> Node<f32, f32> {
+	Node {
+		name: LocalizedStringMap::new(),
+		vertices: [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)].iter().map(|&(x, y)| common::planar(x, y)).collect(),
+		faces: vec![Face::Triangle(Vector3::new(0, 1, 2)), Face::Triangle(Vector3::new(3, 4, 5))],
+		material: None,
+		uv_names: Vec::new(),
+		color_names: Vec::new(),
+	}
+}
+
+fn offset(vertex: usize, y: f32) -> MorphOffset<f32> {
+	MorphOffset {
+		node: 0,
+		vertex: vertex,
+		position: Vector3::new(0.0, y, 0.0),
+		normal: Vector3::zero(),
+		uv: Vector2::zero(),
+	}
+}
+
+fn object(offsets: Vec<MorphOffset<f32>>) -> Object<f32, f32> {
+	Object {
+		nodes: vec![square()],
+		morphs: vec![Morph { offsets: offsets, ..Default::default() }],
+		..Default::default()
+	}
+}
+
+#[test]
+fn weld_merges_duplicates() {
+	let mut node = square();
+	let (report, remap) = weld(&mut node, &Tolerance::exact());
+
+	assert_eq!(node.vertices.len(), 4);
+	assert_eq!(report.welded, 2);
+	assert_eq!(remap[3], remap[2]);
+	assert_eq!(remap[5], remap[0]);
+	assert_eq!(node.faces[1], Face::Triangle(Vector3::new(2, 3, 0)));
+}
+
+#[test]
+fn optimize_keeps_vertices_a_morph_separates() {
+	// Only one copy of the corner at (1, 1) moves, as along a UV seam that a morph opens
+	let mut object = object(vec![offset(3, 0.5)]);
+	let report = optimize(&mut object, &Tolerance::exact());
+	let node = &object.nodes[0];
+	let moved = object.morphs[0].offsets[0].vertex;
+
+	assert_eq!(report.welded, 1);
+	assert_eq!(node.vertices.len(), 5);
+	assert_eq!(node.vertices[moved].position, Vector3::new(1.0, 1.0, 0.0));
+	assert_eq!(node.vertices.iter().filter(|v| v.position == Vector3::new(1.0, 1.0, 0.0)).count(), 2);
+}
+
+#[test]
+fn optimize_welds_vertices_a_morph_moves_together() {
+	let mut object = object(vec![offset(2, 0.5), offset(3, 0.5)]);
+	let report = optimize(&mut object, &Tolerance::exact());
+
+	assert_eq!(report.welded, 2);
+	assert_eq!(object.nodes[0].vertices.len(), 4);
+	assert_eq!(object.morphs[0].offsets.len(), 1);
+}
+
+#[test]
+fn passes_drop_out_of_range_faces() {
+	let bad = Face::Triangle(Vector3::new(0, 1, 9));
+
+	let mut node = square();
+	node.faces.push(bad.clone());
+	let (report, _) = weld(&mut node, &Tolerance::exact());
+	assert_eq!((report.invalid_faces, node.faces.len()), (1, 2));
+
+	let mut node = square();
+	node.faces.push(bad.clone());
+	let (report, _) = remove_unused(&mut node);
+	assert_eq!((report.invalid_faces, node.faces.len()), (1, 2));
+
+	let mut node = square();
+	node.faces.push(Face::Quad(Vector4::new(0, 1, 2, 9)));
+	let (report, remap) = optimize_vertex_cache(&mut node);
+	assert_eq!((report.invalid_faces, node.faces.len()), (1, 2));
+	assert_eq!(remap.len(), 6);
+
+	let mut object = object(Vec::new());
+	object.nodes[0].faces.push(bad);
+	assert_eq!(optimize(&mut object, &Tolerance::exact()).invalid_faces, 1);
+}
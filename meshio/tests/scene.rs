@@ -0,0 +1,70 @@
+use cgmath::{
+	Matrix4,
+	Vector3
+};
+
+use meshio::*;
+
+fn node(parent: Option<usize>, x: f64) -> SceneNode<f64> {
+	let mut node = SceneNode::new(LocalizedStringMap::new());
+	node.parent = parent;
+	node.transform.translation = Vector3::new(x, 0.0, 0.0);
+	node
+}
+
+#[test]
+fn nodes_follow_parents_and_joints() {
+	let mut head = Joint::new(LocalizedStringMap::new());
+	head.parent = Some(0);
+	head.translation = Vector3::new(0.0, 2.0, 0.0);
+	let mut root = Joint::new(LocalizedStringMap::new());
+	root.translation = Vector3::new(0.0, 1.0, 0.0);
+
+	let mut scene: Scene<f64, f32> = Scene::default();
+	scene.objects.push(Object {
+		skeleton: Some(Skeleton::new(vec![root, head]).unwrap()),
+		..Default::default()
+	});
+
+	// Children may come before their parents
+	let mut hat = node(Some(2), 0.5);
+	hat.joint = Some(1);
+	let mut figure = node(None, 10.0);
+	figure.object = Some(0);
+	scene.add_node(hat);
+	scene.add_node(node(Some(0), 1.0));
+	scene.add_node(figure);
+	let mut missing = node(Some(2), 0.0);
+	missing.joint = Some(7);
+	scene.add_node(missing);
+
+	let world = scene.world_matrices().unwrap();
+	assert_eq!(world[2], Matrix4::from_translation(Vector3::new(10.0, 0.0, 0.0)));
+	assert_eq!(world[0], Matrix4::from_translation(Vector3::new(10.5, 3.0, 0.0)));
+	assert_eq!(world[1], Matrix4::from_translation(Vector3::new(11.5, 3.0, 0.0)));
+	// A missing joint is treated as no joint
+	assert_eq!(world[3], world[2]);
+}
+
+#[test]
+fn broken_hierarchies() {
+	let mut scene: Scene<f64, f32> = Scene::default();
+	scene.add_node(node(None, 0.0));
+	scene.add_node(node(Some(4), 0.0));
+	assert_eq!(scene.world_matrices(), Err(NodeHierarchyError::ParentOutOfRange { node: 1, parent: 4 }));
+
+	scene.nodes[1].parent = Some(2);
+	scene.add_node(node(Some(1), 0.0));
+	assert_eq!(scene.world_matrices(), Err(NodeHierarchyError::Cycle { node: 1 }));
+	assert_eq!(NodeHierarchyError::Cycle { node: 1 }.to_string(), "node 1 is its own ancestor");
+}
+
+#[test]
+fn cycle_below_an_acyclic_node() {
+	// Node 0 leads into the cycle between 1 and 2 without being on it
+	let mut scene: Scene<f64, f32> = Scene::default();
+	scene.add_node(node(Some(1), 0.0));
+	scene.add_node(node(Some(2), 0.0));
+	scene.add_node(node(Some(1), 0.0));
+	assert_eq!(scene.world_matrices(), Err(NodeHierarchyError::Cycle { node: 1 }));
+}
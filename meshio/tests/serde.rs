@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+#![allow(clippy::redundant_field_names)]
+
+use cgmath::{
+	Vector2,
+	Vector3,
+	Vector4
+};
+
+use meshio::*;
+
+mod common;
+
+fn scene() -> SceneF {
+	let mut name = LocalizedStringMap::new();
+	name.insert(Language::Japanese, "三角".to_owned());
+	name.insert(Language::Other("de".to_owned()), "Dreieck".to_owned());
+
+	let vertex = |x: f32, y: f32| {
+		let mut vertex = Vertex {
+			tangent: Vector4::new(1.0, 0.0, 0.0, 1.0),
+			colors: vec![ColorF { red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0 }],
+			weights: SkinWeights::single(0),
+			..common::planar(x, y)
+		};
+		vertex.uvs.push(Vector2::new(y, x));
+		vertex
+	};
+	let node = Node {
+		name: name.clone(),
+		vertices: vec![vertex(0.0, 0.0), vertex(1.0, 0.0), vertex(0.0, 1.0)],
+		faces: vec![Face::Triangle(Vector3::new(0, 1, 2))],
+		material: Some(0),
+		uv_names: vec!["base".to_owned(), "lightmap".to_owned()],
+		color_names: Vec::new(),
+	};
+	let object = Object {
+		name: name.clone(),
+		nodes: vec![node],
+		skeleton: Some(Skeleton::new(vec![Joint::new(name.clone())]).unwrap()),
+		..Default::default()
+	};
+
+	Scene {
+		name: name,
+		objects: vec![object],
+		materials: vec![Material::default()],
+		nodes: vec![SceneNode { object: Some(0), ..SceneNode::new(LocalizedStringMap::new()) }],
+		lights: Vec::new(),
+		cameras: vec![Camera::new(LocalizedStringMap::new(), 1.0)],
+		coordinate_system: CoordinateSystem::MMD,
+	}
+}
+
+#[test]
+fn scene_round_trips_through_json() {
+	let scene = scene();
+	let json = serde_json::to_string_pretty(&scene).unwrap();
+	let back: SceneF = serde_json::from_str(&json).unwrap();
+
+	assert_eq!(back, scene);
+}
+
+#[test]
+fn languages_serialize_as_tags() {
+	let json = serde_json::to_string(&scene().name).unwrap();
+
+	assert!(json.contains("\"ja\":\"三角\""));
+	assert!(json.contains("\"de\":\"Dreieck\""));
+}
+
+#[test]
+fn skeleton_rejects_children_before_parents() {
+	let json = r#"[{"name":{},"parent":1,"translation":{"x":0,"y":0,"z":0},"rotation":{"s":1,"v":{"x":0,"y":0,"z":0}},"scale":{"x":1,"y":1,"z":1},"inverse_bind":{"x":{"x":1,"y":0,"z":0,"w":0},"y":{"x":0,"y":1,"z":0,"w":0},"z":{"x":0,"y":0,"z":1,"w":0},"w":{"x":0,"y":0,"z":0,"w":1}}},{"name":{},"parent":null,"translation":{"x":0,"y":0,"z":0},"rotation":{"s":1,"v":{"x":0,"y":0,"z":0}},"scale":{"x":1,"y":1,"z":1},"inverse_bind":{"x":{"x":1,"y":0,"z":0,"w":0},"y":{"x":0,"y":1,"z":0,"w":0},"z":{"x":0,"y":0,"z":1,"w":0},"w":{"x":0,"y":0,"z":0,"w":1}}}]"#;
+
+	assert!(serde_json::from_str::<Skeleton<f32>>(json).is_err());
+}
@@ -0,0 +1,61 @@
+use cgmath::Vector3;
+
+use meshio::*;
+
+/// Creates joints with the given parents, each named after its original index and offset
+/// along X by it
+fn joints(parents: &[Option<usize>]) -> Vec<Joint<f32>> {
+	parents.iter().enumerate().map(|(i, &parent)| {
+		let mut name = LocalizedStringMap::new();
+		name.insert(Language::English, i.to_string());
+		let mut joint = Joint::new(name);
+		joint.parent = parent;
+		joint.translation = Vector3::new(i as f32, 0.0, 0.0);
+		joint
+	}).collect()
+}
+
+#[test]
+fn from_unordered_moves_parents_first() {
+	// 2 is the root, with children 0 and 3 in that order, and 1 is a child of 0
+	let (skeleton, remap) = Skeleton::from_unordered(joints(&[Some(2), Some(0), None, Some(2)])).unwrap();
+
+	assert_eq!(remap, [1, 2, 0, 3]);
+	let parents: Vec<Option<usize>> = skeleton.joints().iter().map(|j| j.parent).collect();
+	assert_eq!(parents, [None, Some(0), Some(1), Some(0)]);
+	for (old, &new) in remap.iter().enumerate() {
+		assert_eq!(skeleton.find(&old.to_string()), Some(new));
+		assert_eq!(skeleton.joints()[new].translation.x, old as f32);
+	}
+
+	// Ordered input is left as it is
+	let (ordered, remap) = Skeleton::from_unordered(joints(&[None, Some(0), Some(0)])).unwrap();
+	assert_eq!(remap, [0, 1, 2]);
+	assert_eq!(ordered, Skeleton::new(joints(&[None, Some(0), Some(0)])).unwrap());
+}
+
+#[test]
+fn from_unordered_rejects_bad_parents() {
+	assert_eq!(
+		Skeleton::from_unordered(joints(&[None, Some(3)])),
+		Err(HierarchyError::ParentOutOfRange { joint: 1, parent: 3 })
+	);
+	assert_eq!(Skeleton::from_unordered(joints(&[Some(0)])), Err(HierarchyError::Cycle { joint: 0 }));
+}
+
+#[test]
+fn from_unordered_reports_a_joint_on_the_cycle() {
+	// 1 hangs off the cycle between 2 and 3 without being part of it
+	let error = Skeleton::from_unordered(joints(&[None, Some(3), Some(3), Some(2)])).unwrap_err();
+	assert_eq!(error, HierarchyError::Cycle { joint: 3 });
+	assert_eq!(error.to_string(), "joint 3 is its own ancestor");
+}
+
+#[test]
+fn new_requires_parents_first() {
+	assert_eq!(
+		Skeleton::new(joints(&[Some(1), None])),
+		Err(HierarchyError::ParentAfterChild { joint: 0, parent: 1 })
+	);
+	assert_eq!(Skeleton::new(joints(&[None, Some(1)])), Err(HierarchyError::Cycle { joint: 1 }));
+}
@@ -0,0 +1,87 @@
+use meshio::*;
+
+fn pairs(weights: &SkinWeights<f32>) -> Vec<(usize, f32)> {
+	weights.influences.iter().map(|i| (i.joint, i.weight)).collect()
+}
+
+#[test]
+fn from_slices_merges_and_skips() {
+	let weights = SkinWeights::from_slices(&[2, 0, 2, 1], &[0.25, 0.5, 0.25, 0.0]);
+	assert_eq!(pairs(&weights), [(2, 0.5), (0, 0.5)]);
+	assert_eq!(weights.total(), 1.0);
+}
+
+#[test]
+fn normalize_scales_to_one() {
+	let mut weights = SkinWeights::from_slices(&[0, 1], &[1.0, 3.0]);
+	weights.normalize();
+	assert_eq!(pairs(&weights), [(0, 0.25), (1, 0.75)]);
+
+	// All-zero weights cannot be scaled and are left alone
+	let mut zero = SkinWeights { influences: vec![Influence { joint: 0, weight: 0.0f32 }] };
+	zero.normalize();
+	assert_eq!(pairs(&zero), [(0, 0.0)]);
+}
+
+#[test]
+fn prune_keeps_the_heaviest() {
+	let mut weights = SkinWeights::from_slices(&[0, 1, 2, 3], &[0.125, 0.5, 0.125, 0.25]);
+	weights.prune(2);
+	assert_eq!(pairs(&weights), [(1, 0.5 / 0.75), (3, 0.25 / 0.75)]);
+
+	weights.prune(4);
+	assert_eq!(weights.len(), 2);
+}
+
+#[test]
+fn remove_below_renormalizes() {
+	let mut weights = SkinWeights::from_slices(&[0, 1, 2], &[0.5, 0.25, 0.25]);
+	weights.remove_below(0.3);
+	assert_eq!(pairs(&weights), [(0, 1.0)]);
+}
+
+#[test]
+fn quantized_weights_sum_exactly_to_the_scale() {
+	let thirds = SkinWeights::from_slices(&[0, 1, 2], &[1.0f32 / 3.0, 1.0 / 3.0, 1.0 / 3.0]);
+	let bytes = thirds.quantize_u8();
+	assert_eq!(bytes.iter().map(|&w| u32::from(w)).sum::<u32>(), 255);
+	assert_eq!(bytes, [85, 85, 85]);
+
+	// The rounding remainder goes to the largest fractions
+	let uneven = SkinWeights::from_slices(&[0, 1, 2, 3], &[0.7f32, 0.1, 0.1, 0.1]);
+	assert_eq!(uneven.quantize(7), [5, 1, 1, 0]);
+	assert_eq!(uneven.quantize_u8().iter().map(|&w| u32::from(w)).sum::<u32>(), 255);
+	assert_eq!(uneven.quantize_u16().iter().map(|&w| u32::from(w)).sum::<u32>(), 65535);
+
+	// Unnormalized weights are scaled first
+	let mut heavy = uneven.clone();
+	heavy.influences.iter_mut().for_each(|i| i.weight *= 3.0);
+	assert_eq!(heavy.quantize_u8(), uneven.quantize_u8());
+
+	assert_eq!(SkinWeights::<f32>::default().quantize_u8(), Vec::<u8>::new());
+	assert_eq!(SkinWeights { influences: vec![Influence { joint: 0, weight: 0.0f32 }] }.quantize_u8(), [0]);
+}
+
+#[test]
+fn dequantize_inverts_quantize() {
+	let weights = SkinWeights::<f32>::dequantize(&[4, 2, 9], &[128, 127, 0], 255);
+	assert_eq!(pairs(&weights), [(4, 128.0 / 255.0), (2, 127.0 / 255.0)]);
+	assert_eq!(weights.quantize_u8(), [128, 127]);
+}
+
+#[test]
+fn remap_drops_joints_outside_the_table() {
+	let mut weights = SkinWeights::from_slices(&[0, 1, 5], &[0.5, 0.25, 0.25]);
+	weights.remap(&[1, 0]);
+	assert_eq!(pairs(&weights), [(1, 0.5), (0, 0.25)]);
+
+	let mut physics = physics::Physics::default();
+	for &joint in [Some(1), Some(5), None].iter() {
+		let mut body = physics::RigidBody::new(LocalizedStringMap::new(), physics::Shape::Sphere { radius: 1.0f32 });
+		body.joint = joint;
+		physics.bodies.push(body);
+	}
+	physics.remap(&[1, 0]);
+	let joints: Vec<Option<usize>> = physics.bodies.iter().map(|b| b.joint).collect();
+	assert_eq!(joints, [Some(0), None, None]);
+}
@@ -0,0 +1,95 @@
+use cgmath::{
+	Vector3,
+	Vector4
+};
+
+use meshio::*;
+
+mod common;
+
+fn positions(points: &[(f32, f32)]) -> Vec<Vector3<f32>> {
+	points.iter().map(|&(x, y)| Vector3::new(x, y, 0.0)).collect()
+}
+
+fn polygon(points: &[(f32, f32)]) -> std::result::Result<Vec<Vector3<u32>>, Degenerate> {
+	Face::from_indices((0..points.len() as u32).collect()).triangulate(&positions(points))
+}
+
+/// Returns the summed signed area of triangles in the XY plane
+fn area(points: &[(f32, f32)], triangles: &[Vector3<u32>]) -> f32 {
+	let p = positions(points);
+	triangles.iter()
+		.map(|t| {
+			let (a, b, c) = (p[t.x as usize], p[t.y as usize], p[t.z as usize]);
+			(b - a).cross(c - a).z / 2.0
+		})
+		.sum()
+}
+
+#[test]
+fn convex_quad_splits_along_shorter_diagonal() {
+	let quad = [(0.0, 0.0), (3.0, 0.0), (3.0, 1.0), (0.0, 1.0)];
+	let kite = [(0.0, 0.0), (2.0, -1.0), (4.0, 0.0), (2.0, 1.0)];
+	assert_eq!(polygon(&quad).unwrap().len(), 2);
+	assert_eq!(polygon(&kite), Ok(vec![Vector3::new(0, 1, 3), Vector3::new(1, 2, 3)]));
+}
+
+#[test]
+fn concave_polygon_is_covered() {
+	let arrow = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (2.0, 1.0), (0.0, 4.0)];
+	let triangles = polygon(&arrow).unwrap();
+
+	assert_eq!(triangles.len(), 3);
+	assert_eq!(area(&arrow, &triangles), 10.0);
+	assert!(triangles.iter().all(|t| area(&arrow, &[*t]) > 0.0));
+}
+
+#[test]
+fn collinear_corners() {
+	// A corner in the middle of an edge still gets a triangle of its own
+	let square = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+	let triangles = polygon(&square).unwrap();
+	assert_eq!(triangles.len(), 3);
+	assert_eq!(area(&square, &triangles), 4.0);
+
+	// A spike that folds back along itself leaves a collinear remainder
+	let spike = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (1.0, 2.0), (1.0, 3.0), (1.0, 2.0), (0.0, 2.0)];
+	assert_eq!(polygon(&spike), Err(Degenerate::NotSimple));
+
+	let line = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)];
+	assert_eq!(polygon(&line), Err(Degenerate::ZeroArea));
+}
+
+#[test]
+fn self_intersecting_polygons() {
+	let crossed = [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (1.0, -1.0), (0.0, 3.0)];
+	assert_eq!(polygon(&crossed), Err(Degenerate::NotSimple));
+
+	// The lobes of a symmetric bowtie cancel out
+	let bowtie = [(0.0, 0.0), (4.0, 0.0), (0.0, 2.0), (4.0, 2.0)];
+	assert_eq!(polygon(&bowtie), Err(Degenerate::ZeroArea));
+}
+
+#[test]
+fn node_reports_dropped_faces() {
+	let points = [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (1.0, -1.0), (0.0, 3.0)];
+	let mut node: Node<f32, f32> = Node {
+		name: LocalizedStringMap::new(),
+		vertices: positions(&points).into_iter().map(common::vertex).collect(),
+		faces: vec![
+			Face::from_indices(vec![0, 1, 2, 3, 4]),
+			Face::Quad(Vector4::new(0, 1, 2, 4)),
+			Face::Triangle(Vector3::new(0, 1, 7)),
+		],
+		material: None,
+		uv_names: Vec::new(),
+		color_names: Vec::new(),
+	};
+
+	let dropped = node.triangulate();
+	assert_eq!(dropped, vec![
+		DegenerateFace { face: 0, reason: Degenerate::NotSimple },
+		DegenerateFace { face: 2, reason: Degenerate::IndexOutOfRange(7) },
+	]);
+	assert_eq!(node.faces.len(), 2);
+}
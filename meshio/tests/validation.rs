@@ -0,0 +1,239 @@
+use cgmath::{
+	Vector2,
+	Vector3,
+	Zero
+};
+
+use std::path::PathBuf;
+
+use meshio::*;
+
+mod common;
+
+/// A valid scene of one textured triangle skinned to a two joint skeleton, placed by a node
+/// with a child following the second joint
+fn scene() -> SceneF {
+	let mut material = Material::default();
+	material.textures.insert(TextureSlot::Diffuse, Texture::new("tests\\validation.rs"));
+
+	let mut child = Joint::new(LocalizedStringMap::new());
+	child.parent = Some(0);
+	let skeleton = Skeleton::new(vec![Joint::new(LocalizedStringMap::new()), child]).unwrap();
+
+	let node = Node {
+		vertices: [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)].iter()
+			.map(|&(x, y)| Vertex { weights: SkinWeights::single(1), ..common::planar(x, y) })
+			.collect(),
+		faces: vec![Face::Triangle(Vector3::new(0, 1, 2))],
+		material: Some(0),
+		..Default::default()
+	};
+
+	let mut scene = Scene::default();
+	scene.materials.push(material);
+	scene.objects.push(Object {
+		nodes: vec![node],
+		skeleton: Some(skeleton),
+		..Default::default()
+	});
+
+	let mut root = SceneNode::new(LocalizedStringMap::new());
+	root.object = Some(0);
+	let mut attached = SceneNode::new(LocalizedStringMap::new());
+	attached.parent = Some(0);
+	attached.joint = Some(1);
+	scene.add_node(root);
+	scene.add_node(attached);
+	scene
+}
+
+fn options() -> ValidationOptions {
+	ValidationOptions {
+		texture_root: Some(PathBuf::from(env!("CARGO_MANIFEST_DIR"))),
+		..Default::default()
+	}
+}
+
+/// Returns the only diagnostic of the scene
+fn single(scene: &SceneF) -> Diagnostic {
+	let mut diagnostics = validate_with(scene, &options());
+	assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+	diagnostics.remove(0)
+}
+
+const NODE: Location = Location::Node { object: 0, node: 0 };
+
+fn node(scene: &mut SceneF) -> &mut Node<f32, f32> {
+	&mut scene.objects[0].nodes[0]
+}
+
+#[test]
+fn valid_scene_has_no_diagnostics() {
+	assert_eq!(validate_with(&scene(), &options()), Vec::new());
+}
+
+#[test]
+fn dangling_face_index() {
+	let mut scene = scene();
+	node(&mut scene).faces.push(Face::Triangle(Vector3::new(0, 1, 3)));
+
+	let d = single(&scene);
+	assert_eq!((d.severity, d.location), (Severity::Error, NODE));
+	assert_eq!(d.issue, Issue::FaceIndexOutOfRange { count: 1, first_face: 1 });
+}
+
+#[test]
+fn degenerate_faces() {
+	let mut scene = scene();
+	node(&mut scene).faces.push(Face::Triangle(Vector3::new(0, 1, 1)));
+	node(&mut scene).faces.push(Face::Ngon(vec![0, 1]));
+
+	assert_eq!(single(&scene).issue, Issue::DegenerateFaces { count: 2, first_face: 1, reason: Degenerate::ZeroArea });
+}
+
+#[test]
+fn nan_position() {
+	let mut scene = scene();
+	node(&mut scene).vertices[2].position.y = f32::NAN;
+
+	let d = single(&scene);
+	assert_eq!(d.severity, Severity::Error);
+	assert_eq!(d.issue, Issue::NonFinite { attribute: Attribute::Position, count: 1, first_vertex: 2 });
+}
+
+#[test]
+fn infinite_uv() {
+	let mut scene = scene();
+	node(&mut scene).vertices[1].uvs[0].x = f32::INFINITY;
+	assert_eq!(single(&scene).issue, Issue::NonFinite { attribute: Attribute::Uv, count: 1, first_vertex: 1 });
+}
+
+#[test]
+fn non_unit_normal() {
+	let mut scene = scene();
+	node(&mut scene).vertices[1].normals[0] = Vector3::new(0.0, 0.0, 2.0);
+	node(&mut scene).vertices[2].normals[0] = Vector3::new(0.0, 0.0, 1.0005);
+
+	let d = single(&scene);
+	assert_eq!(d.severity, Severity::Warning);
+	assert_eq!(d.issue, Issue::NonUnitNormals { count: 1, first_vertex: 1 });
+}
+
+#[test]
+fn unnormalized_weights() {
+	let mut scene = scene();
+	node(&mut scene).vertices[0].weights = SkinWeights::from_slices(&[0, 1], &[0.5, 0.25]);
+	// Unskinned vertices are fine
+	node(&mut scene).vertices[1].weights = SkinWeights::default();
+
+	assert_eq!(single(&scene).issue, Issue::UnnormalizedWeights { count: 1, first_vertex: 0 });
+}
+
+#[test]
+fn weight_joint_out_of_range() {
+	let mut scene = scene();
+	node(&mut scene).vertices[2].weights = SkinWeights::single(2);
+	assert_eq!(single(&scene).issue, Issue::WeightJointOutOfRange { count: 1, first_vertex: 2 });
+}
+
+#[test]
+fn mismatched_sets() {
+	let mut scene = scene();
+	node(&mut scene).vertices[1].colors.push(ColorF { red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0 });
+	assert_eq!(single(&scene).issue, Issue::MismatchedSets { count: 1, first_vertex: 1 });
+}
+
+#[test]
+fn missing_uv_set() {
+	let mut scene = scene();
+	scene.materials[0].textures.get_mut(&TextureSlot::Diffuse).unwrap().uv_set = 1;
+	assert_eq!(single(&scene).issue, Issue::MissingUvSet { uv_set: 1, count: 3, first_vertex: 0 });
+}
+
+#[test]
+fn empty_node() {
+	let mut scene = scene();
+	node(&mut scene).faces.clear();
+
+	let d = single(&scene);
+	assert_eq!((d.severity, d.location, d.issue), (Severity::Warning, NODE, Issue::EmptyNode));
+}
+
+#[test]
+fn index_out_of_range() {
+	let mut scene = scene();
+	node(&mut scene).material = Some(1);
+	scene.objects[0].morphs.push(Morph {
+		offsets: vec![MorphOffset { node: 0, vertex: 3, position: Vector3::zero(), normal: Vector3::zero(), uv: Vector2::zero() }],
+		..Default::default()
+	});
+	scene.nodes[0].camera = Some(0);
+
+	let issues: Vec<(Location, Issue)> = validate_with(&scene, &options()).into_iter().map(|d| (d.location, d.issue)).collect();
+	assert_eq!(issues, [
+		(Location::SceneNode(0), Issue::IndexOutOfRange { field: "camera", index: 0, len: 0 }),
+		(NODE, Issue::IndexOutOfRange { field: "material", index: 1, len: 1 }),
+		(Location::Morph { object: 0, morph: 0 }, Issue::IndexOutOfRange { field: "offset vertex", index: 3, len: 3 }),
+	]);
+}
+
+#[test]
+fn joint_on_a_root_scene_node() {
+	let mut scene = scene();
+	scene.nodes[1].parent = None;
+
+	let d = single(&scene);
+	assert_eq!((d.severity, d.location), (Severity::Error, Location::SceneNode(1)));
+	assert_eq!(d.issue, Issue::IndexOutOfRange { field: "joint", index: 1, len: 0 });
+}
+
+#[test]
+fn scene_node_cycle() {
+	let mut scene = scene();
+	scene.nodes[0].parent = Some(1);
+	scene.nodes[1].joint = None;
+
+	let d = single(&scene);
+	assert_eq!((d.severity, d.location), (Severity::Error, Location::Scene));
+	assert_eq!(d.issue, Issue::NodeHierarchy(NodeHierarchyError::Cycle { node: 0 }));
+
+	// A node leading into the cycle is not reported
+	scene.nodes[0].parent = None;
+	scene.nodes[1].parent = Some(2);
+	for &parent in [3, 2].iter() {
+		let mut looped = SceneNode::new(LocalizedStringMap::new());
+		looped.parent = Some(parent);
+		scene.add_node(looped);
+	}
+	assert_eq!(single(&scene).issue, Issue::NodeHierarchy(NodeHierarchyError::Cycle { node: 2 }));
+}
+
+#[test]
+fn joint_cycle() {
+	let mut scene = scene();
+	let skeleton = scene.objects[0].skeleton.as_mut().unwrap();
+	skeleton.joint_mut(0).unwrap().parent = Some(1);
+
+	let d = single(&scene);
+	assert_eq!(d.location, Location::Joint { object: 0, joint: 0 });
+	assert_eq!(d.issue, Issue::Hierarchy(HierarchyError::ParentAfterChild { joint: 0, parent: 1 }));
+
+	let skeleton = scene.objects[0].skeleton.as_mut().unwrap();
+	skeleton.joint_mut(0).unwrap().parent = Some(0);
+	assert_eq!(single(&scene).issue, Issue::Hierarchy(HierarchyError::Cycle { joint: 0 }));
+}
+
+#[test]
+fn texture_paths() {
+	let mut scene = scene();
+	scene.materials[0].textures.insert(TextureSlot::Normal, Texture::new("missing.png"));
+
+	let d = single(&scene);
+	assert_eq!((d.severity, d.location), (Severity::Warning, Location::Material(0)));
+	assert_eq!(d.issue, Issue::MissingTexture(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("missing.png")));
+	// Files are only looked up with a texture root
+	assert_eq!(validate(&scene), Vec::new());
+
+	scene.materials[0].textures.insert(TextureSlot::Normal, Texture::new(""));
+	assert_eq!(single(&scene).issue, Issue::EmptyTexturePath);
+}
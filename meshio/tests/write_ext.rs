@@ -0,0 +1,103 @@
+use cgmath::{
+	Matrix4,
+	Vector2,
+	Vector3,
+	Vector4
+};
+
+use meshio::*;
+
+fn written<F: FnOnce(&mut Vec<u8>) -> std::io::Result<()>>(write: F) -> Vec<u8> {
+	let mut buf = Vec::new();
+	write(&mut buf).unwrap();
+	buf
+}
+
+#[test]
+fn vectors_round_trip() {
+	let v2 = Vector2::new(1.5f32, -2.25);
+	let v3f = Vector3::new(1.0f32, -0.5, 3.75);
+	let v3d = Vector3::new(1.0e300f64, -0.125, 7.0);
+	let v4 = Vector4::new(0.0f32, 1.0, 2.0, -3.0);
+
+	assert_eq!(le_v2f(&written(|w| w.write_le_v2f(v2))).unwrap(), (&[][..], v2));
+	assert_eq!(le_v3f(&written(|w| w.write_le_v3f(v3f))).unwrap(), (&[][..], v3f));
+	assert_eq!(be_v3f(&written(|w| w.write_be_v3f(v3f))).unwrap(), (&[][..], v3f));
+	assert_eq!(le_v3d(&written(|w| w.write_le_v3d(v3d))).unwrap(), (&[][..], v3d));
+	assert_eq!(be_v3d(&written(|w| w.write_be_v3d(v3d))).unwrap(), (&[][..], v3d));
+	assert_eq!(le_v4f(&written(|w| w.write_le_v4f(v4))).unwrap(), (&[][..], v4));
+}
+
+#[test]
+fn byte_order_matches_parsers() {
+	let v = Vector3::new(1.0f32, 2.0, 3.0);
+	assert_eq!(&written(|w| w.write_le_v3f(v))[..4], &1.0f32.to_le_bytes());
+	assert_eq!(&written(|w| w.write_be_v3f(v))[..4], &1.0f32.to_be_bytes());
+}
+
+#[test]
+fn matrix_round_trip() {
+	let m = Matrix4::new(
+		1.0f32, 2.0, 3.0, 0.0,
+		4.0, 5.0, 6.0, 0.0,
+		7.0, 8.0, 9.0, 0.0,
+		10.0, 11.0, 12.0, 1.0,
+	);
+	let buf = written(|w| w.write_le_mat4x3(&m));
+
+	assert_eq!(buf.len(), 48);
+	assert_eq!(le_mat4x3(&buf).unwrap(), (&[][..], m));
+}
+
+#[test]
+fn bounds_round_trip() {
+	let b32 = Bounds32 { min: Vector3::new(-1.0, -2.0, -3.0), max: Vector3::new(1.0, 2.0, 3.0), radius: Some(4.0) };
+	let b64 = Bounds64 { min: Vector3::new(-1.0, -2.0, -3.0), max: Vector3::new(1.0, 2.0, 3.0), radius: Some(4.0) };
+	let b32_n = Bounds32 { radius: None, ..b32.clone() };
+	let b64_n = Bounds64 { radius: None, ..b64.clone() };
+
+	assert_eq!(le_bounds32_r(&written(|w| w.write_le_bounds32_r(&b32))).unwrap().1, b32);
+	assert_eq!(le_bounds64_r(&written(|w| w.write_le_bounds64_r(&b64))).unwrap().1, b64);
+	assert_eq!(be_bounds32_r(&written(|w| w.write_be_bounds32_r(&b32))).unwrap().1, b32);
+	assert_eq!(be_bounds64_r(&written(|w| w.write_be_bounds64_r(&b64))).unwrap().1, b64);
+	assert_eq!(le_bounds32(&written(|w| w.write_le_bounds32(&b32_n))).unwrap().1, b32_n);
+	assert_eq!(le_bounds64(&written(|w| w.write_le_bounds64(&b64_n))).unwrap().1, b64_n);
+	assert_eq!(be_bounds32(&written(|w| w.write_be_bounds32(&b32_n))).unwrap().1, b32_n);
+	assert_eq!(be_bounds64(&written(|w| w.write_be_bounds64(&b64_n))).unwrap().1, b64_n);
+}
+
+#[test]
+fn bounds_without_radius_write_zero() {
+	let b = Bounds32 { min: Vector3::new(0.0, 0.0, 0.0), max: Vector3::new(1.0, 1.0, 1.0), radius: None };
+	assert_eq!(le_bounds32_r(&written(|w| w.write_le_bounds32_r(&b))).unwrap().1.radius, Some(0.0));
+}
+
+#[test]
+fn colors_round_trip() {
+	let b = ColorB { red: 1, green: 2, blue: 3, alpha: 4 };
+	let f = ColorF { red: 0.25, green: 0.5, blue: 0.75, alpha: 0.125 };
+	let opaque = ColorF { alpha: 1.0, ..f.clone() };
+
+	assert_eq!(written(|w| w.write_le_rgba_b(&b)), vec![1, 2, 3, 4]);
+	assert_eq!(le_rgba_b(&written(|w| w.write_le_rgba_b(&b))).unwrap().1, b);
+	assert_eq!(le_argb_b(&written(|w| w.write_le_argb_b(&b))).unwrap().1, b);
+	assert_eq!(le_bgra_b(&written(|w| w.write_le_bgra_b(&b))).unwrap().1, b);
+	assert_eq!(be_argb_b(&written(|w| w.write_be_argb_b(&b))).unwrap().1, b);
+	assert_eq!(be_bgra_b(&written(|w| w.write_be_bgra_b(&b))).unwrap().1, b);
+	assert_eq!(le_rgba_f(&written(|w| w.write_le_rgba_f(&f))).unwrap().1, f);
+	assert_eq!(le_argb_f(&written(|w| w.write_le_argb_f(&f))).unwrap().1, f);
+	assert_eq!(be_rgba_f(&written(|w| w.write_be_rgba_f(&f))).unwrap().1, f);
+	assert_eq!(le_rgb_f(&written(|w| w.write_le_rgb_f(&f))).unwrap().1, opaque);
+	assert_eq!(be_rgb_f(&written(|w| w.write_be_rgb_f(&f))).unwrap().1, opaque);
+}
+
+#[test]
+fn strings_round_trip() {
+	let buf = written(|w| w.write_zstr("ボーン"));
+	assert_eq!(buf.last(), Some(&0));
+	assert_eq!(zstr(&buf).unwrap(), (&b"\0"[..], "ボーン"));
+
+	let buf = written(|w| w.write_le_lstr("bone"));
+	assert_eq!(&buf[..4], &4u32.to_le_bytes());
+	assert_eq!(le_lstr(&buf).unwrap(), (&[][..], "bone"));
+}